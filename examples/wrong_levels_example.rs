@@ -53,6 +53,17 @@ impl Default for MyApp {
                     street_len: 0..=0,
                     lava_n: 0..=0,
                     lava_radius: 0..=0,
+                    lava_host_tiles: vec![],
+                    lava_min_distance: 0,
+                    lava_spawn_distance: 0,
+                    lava_attempts: 0,
+                    settlement_n: 0..=0,
+                    settlement_radius: 0..=0,
+                    settlement_density: 0.0,
+                    bridge_max_len: 0,
+                    volcano_n: 0..=0,
+                    volcano_flow_len: 0..=0,
+                    volcano_crater_radius: 0..=0,
                 })
                 .unwrap()
                 .build()
@@ -1,4 +1,652 @@
+use rand::prelude::StdRng;
+use rand::SeedableRng;
+use robotics_lib::world::tile::{Content, TileType};
+use robotics_lib::world::world_generator::Generator;
+
+use crate::utils::errors::OxAgError;
+use crate::utils::traits::{Loadable, Validator};
+use crate::world_generator::content_options::{
+    OxAgAnchor, OxAgBatchShape, OxAgCapacityOptions, OxAgCollisionPolicy, OxAgContentOptions,
+    OxAgValueDistribution,
+};
+use crate::world_generator::placement_report::OxAgPlacementFailure;
+use crate::world_generator::presets::content_presets::OxAgContentPresets;
+use crate::world_generator::presets::tile_type_presets::OxAgTileTypePresets;
+use crate::world_generator::regions::OxAgRegion;
+use crate::world_generator::spawning_tools::poisson_spawn::poisson_disk;
+use crate::world_generator::world_generator_builder::OxAgWorldGeneratorBuilder;
+
 #[test]
 fn t() {
     assert_eq!(2, 2);
 }
+
+#[test]
+fn poisson_disk_keeps_spacing() {
+    let candidates = (0..50)
+        .flat_map(|row| (0..50).map(move |col| (row, col)))
+        .collect::<Vec<(usize, usize)>>();
+    let existing = [(10, 10), (30, 40)];
+    let points = poisson_disk(
+        candidates,
+        &existing,
+        5,
+        1000,
+        &mut StdRng::seed_from_u64(0),
+    );
+    assert!(points.len() > 20);
+    for (i, &(r1, c1)) in points.iter().enumerate() {
+        for &(r2, c2) in points[i + 1..].iter().chain(&existing) {
+            assert!(r1.abs_diff(r2).pow(2) + c1.abs_diff(c2).pow(2) >= 25);
+        }
+    }
+}
+
+#[test]
+fn exact_count_is_exact() {
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(3)
+        .set_size(64)
+        .set_with_info(false)
+        .set_content_options_from_preset(OxAgContentPresets::Default)
+        .alter_content_option(
+            Content::Coin(0),
+            OxAgContentOptions {
+                exact_count: Some(17),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+    let map = generator.gen().0;
+    let coins = map
+        .iter()
+        .flatten()
+        .filter(|tile| matches!(tile.content, Content::Coin(_)))
+        .count();
+    assert_eq!(coins, 17);
+    let report = generator.get_placement_report().last().unwrap();
+    assert_eq!(
+        (report.requested, report.placed, report.failed),
+        (17, 17, 0)
+    );
+}
+
+#[test]
+fn try_generate_reports_unplaceable_content() {
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(3)
+        .set_size(64)
+        .set_with_info(false)
+        .set_content_options_from_preset(OxAgContentPresets::None)
+        .alter_content_option(
+            Content::Coin(0),
+            OxAgContentOptions {
+                exact_count: Some(5),
+                // There are no teleports in the open world
+                required_neighbours: vec![TileType::Teleport(false)],
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+    assert!(matches!(
+        generator.try_generate(),
+        Err(OxAgError::ContentPlacementFailed(Content::Coin(_)))
+    ));
+}
+
+#[test]
+fn batches_report_the_tiles_they_miss() {
+    let batches = |required_neighbours| OxAgContentOptions {
+        is_present: true,
+        in_batches: true,
+        with_max_spawn_number: true,
+        max_spawn_number: 10,
+        max_radius: 6,
+        required_neighbours,
+        ..Default::default()
+    };
+    let report = |options| {
+        let mut generator = OxAgWorldGeneratorBuilder::new()
+            .set_seed(3)
+            .set_size(64)
+            .set_with_info(false)
+            .set_content_options_from_preset(OxAgContentPresets::None)
+            .alter_content_option(Content::Tree(0), options)
+            .unwrap()
+            .build()
+            .unwrap();
+        generator.gen();
+        generator.get_placement_report()[0].clone()
+    };
+
+    // Only the tiles next to the water keep the trees of the batches
+    let clipped = report(batches(vec![TileType::ShallowWater]));
+    assert!(clipped.placed > 0);
+    assert!(clipped.failed > 0);
+    assert_eq!(clipped.reasons, vec![OxAgPlacementFailure::Clipped]);
+
+    let nowhere = report(batches(vec![TileType::Teleport(false)]));
+    assert_eq!((nowhere.requested, nowhere.failed), (10, 10));
+    assert_eq!(nowhere.reasons, vec![OxAgPlacementFailure::NoValidTile]);
+}
+
+#[test]
+fn values_follow_their_options() {
+    let rng = &mut StdRng::seed_from_u64(0);
+    let max = Content::Rock(0).properties().max();
+    let fixed = OxAgContentOptions {
+        value_range: Some(0..=max),
+        value_distribution: OxAgValueDistribution::Fixed(max),
+        ..Default::default()
+    };
+    assert!(fixed.validate_for(&Content::Rock(0)).is_ok());
+    assert_eq!(
+        fixed.random_value(&Content::Rock(0), rng, None),
+        Content::Rock(max)
+    );
+
+    let normal = OxAgContentOptions {
+        value_range: Some(1..=max),
+        value_distribution: OxAgValueDistribution::Normal {
+            mean: max as f64 / 2.0,
+            std_dev: max as f64,
+        },
+        ..Default::default()
+    };
+    for _ in 0..100 {
+        match normal.random_value(&Content::Rock(0), rng, None) {
+            Content::Rock(value) => assert!((1..=max).contains(&value)),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    let too_high = OxAgContentOptions {
+        value_range: Some(0..=max + 1),
+        ..Default::default()
+    };
+    assert!(too_high.validate_for(&Content::Rock(0)).is_err());
+
+    // Fixed values are checked against the range they are drawn from
+    let outside = OxAgContentOptions {
+        value_distribution: OxAgValueDistribution::Fixed(max),
+        ..Default::default()
+    };
+    assert!(outside.validate_for(&Content::Rock(0)).is_err());
+    let outside_capacity = OxAgContentOptions {
+        capacity: Some(OxAgCapacityOptions {
+            capacity_range: 5..=8,
+            initial_fill: 0.0..=1.0,
+            distribution: OxAgValueDistribution::Fixed(3),
+        }),
+        ..Default::default()
+    };
+    assert!(outside_capacity.validate_for(&Content::Bin(0..0)).is_err());
+}
+
+#[test]
+fn bins_start_half_full() {
+    let rng = &mut StdRng::seed_from_u64(0);
+    let max = Content::Bin(0..0).properties().max();
+    let capacity = OxAgCapacityOptions {
+        capacity_range: max..=max,
+        initial_fill: 0.5..=0.5,
+        distribution: OxAgValueDistribution::Uniform,
+    };
+    let options = OxAgContentOptions {
+        capacity: Some(capacity),
+        ..Default::default()
+    };
+    assert!(options.validate_for(&Content::Bin(0..0)).is_ok());
+    assert!(options.validate_for(&Content::Rock(0)).is_err());
+    assert_eq!(
+        options.random_value(&Content::Bin(0..0), rng, None),
+        Content::Bin((max as f64 / 2.0).round() as usize..max)
+    );
+}
+
+#[test]
+fn batch_shapes_are_validated() {
+    for (shape, valid) in [
+        (OxAgBatchShape::NoiseBlob { threshold: 0.4 }, true),
+        (OxAgBatchShape::NoiseBlob { threshold: 1.5 }, false),
+        (OxAgBatchShape::GaussianFalloff { sigma: 0.0 }, false),
+        (OxAgBatchShape::Ring { thickness: 0 }, false),
+        (OxAgBatchShape::Streak { width: 3 }, true),
+    ] {
+        let options = OxAgContentOptions {
+            batch_shape: shape,
+            ..Default::default()
+        };
+        assert_eq!(options.validate_for(&Content::Tree(0)).is_ok(), valid);
+    }
+}
+
+#[test]
+fn never_overwrite_keeps_earlier_contents() {
+    let spread = |count, priority| OxAgContentOptions {
+        is_present: true,
+        with_max_spawn_number: true,
+        max_spawn_number: count,
+        min_spacing: 2,
+        collision_policy: OxAgCollisionPolicy::NeverOverwrite,
+        priority,
+        ..Default::default()
+    };
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(3)
+        .set_size(64)
+        .set_with_info(false)
+        .set_content_options_from_preset(OxAgContentPresets::None)
+        // Placed after the trees even if it comes first
+        .alter_content_option(Content::Coin(0), spread(300, 1))
+        .unwrap()
+        .alter_content_option(Content::Tree(0), spread(300, 0))
+        .unwrap()
+        .build()
+        .unwrap();
+    generator.gen();
+    let report = generator.get_placement_report();
+    assert_eq!(report[0].content, Content::Tree(0));
+    assert_eq!(report[0].failed, 0);
+}
+
+#[test]
+fn anchored_contents_stay_near_their_anchor() {
+    let present = |count| OxAgContentOptions {
+        is_present: true,
+        with_max_spawn_number: true,
+        max_spawn_number: count,
+        ..Default::default()
+    };
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(3)
+        .set_size(64)
+        .set_with_info(false)
+        .set_content_options_from_preset(OxAgContentPresets::None)
+        .alter_content_option(
+            Content::Scarecrow,
+            OxAgContentOptions {
+                anchor: Some(OxAgAnchor {
+                    content: Content::Tree(0),
+                    distance: 1..=2,
+                }),
+                ..present(20)
+            },
+        )
+        .unwrap()
+        .alter_content_option(Content::Tree(0), present(30))
+        .unwrap()
+        .build()
+        .unwrap();
+    let map = generator.gen().0;
+    assert!(generator.get_placement_report()[1].placed > 0);
+    for (row, rows) in map.iter().enumerate() {
+        for (col, tile) in rows.iter().enumerate() {
+            if tile.content == Content::Scarecrow {
+                let near_tree = (row.saturating_sub(2)..=(row + 2).min(63)).any(|r| {
+                    (col.saturating_sub(2)..=(col + 2).min(63))
+                        .any(|c| matches!(map[r][c].content, Content::Tree(_)))
+                });
+                assert!(near_tree);
+            }
+        }
+    }
+}
+
+#[test]
+fn anchor_loops_are_rejected() {
+    let anchored = |content| OxAgContentOptions {
+        anchor: Some(OxAgAnchor {
+            content,
+            distance: 1..=2,
+        }),
+        ..Default::default()
+    };
+    let options = vec![
+        (Content::Bin(0..0), anchored(Content::Crate(0..0))),
+        (Content::Crate(0..0), anchored(Content::Bin(0..0))),
+    ];
+    assert!(matches!(
+        options.validate(),
+        Err(OxAgError::InvalidAnchor(_))
+    ));
+
+    let builder = || {
+        OxAgWorldGeneratorBuilder::new()
+            .set_content_options_from_preset(OxAgContentPresets::None)
+            .alter_content_option(Content::Bin(0..0), anchored(Content::Crate(0..0)))
+            .unwrap()
+    };
+    assert!(matches!(
+        builder().alter_content_option(Content::Crate(0..0), anchored(Content::Bin(0..0))),
+        Err(OxAgError::InvalidAnchor(_))
+    ));
+    // A loop closed by the options of a region
+    assert!(matches!(
+        builder().add_region_content_option(
+            OxAgRegion::DistanceFromSpawn(0..=20),
+            Content::Crate(0..0),
+            anchored(Content::Bin(0..0)),
+        ),
+        Err(OxAgError::InvalidAnchor(_))
+    ));
+}
+
+#[test]
+fn region_anchors_come_after_their_anchor() {
+    let present = |count| OxAgContentOptions {
+        is_present: true,
+        with_max_spawn_number: true,
+        max_spawn_number: count,
+        ..Default::default()
+    };
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(3)
+        .set_size(64)
+        .set_with_info(false)
+        .set_content_options_from_preset(OxAgContentPresets::None)
+        .alter_content_option(Content::Scarecrow, present(20))
+        .unwrap()
+        .add_region_content_option(
+            OxAgRegion::Rectangle {
+                from: (0, 0),
+                to: (63, 63),
+            },
+            Content::Scarecrow,
+            OxAgContentOptions {
+                anchor: Some(OxAgAnchor {
+                    content: Content::Tree(0),
+                    distance: 1..=2,
+                }),
+                ..present(20)
+            },
+        )
+        .unwrap()
+        // Its priority alone would place it after the scarecrows
+        .alter_content_option(
+            Content::Tree(0),
+            OxAgContentOptions {
+                priority: 1,
+                ..present(30)
+            },
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+    generator.gen();
+    let report = generator
+        .get_placement_report()
+        .iter()
+        .find(|report| report.content == Content::Scarecrow)
+        .unwrap();
+    assert!(report.placed > 0);
+    assert!(!report
+        .reasons
+        .contains(&OxAgPlacementFailure::AnchorNotFound));
+}
+
+#[test]
+fn min_spacing_holds_across_passes() {
+    let spread = OxAgContentOptions {
+        is_present: true,
+        with_max_spawn_number: true,
+        max_spawn_number: 300,
+        min_spacing: 4,
+        ..Default::default()
+    };
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(3)
+        .set_size(64)
+        .set_with_info(false)
+        .set_content_options_from_preset(OxAgContentPresets::None)
+        .alter_content_option(Content::Coin(0), spread.clone())
+        .unwrap()
+        .add_region_content_option(
+            OxAgRegion::Rectangle {
+                from: (0, 0),
+                to: (31, 63),
+            },
+            Content::Coin(0),
+            spread,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+    let coins = generator
+        .gen()
+        .0
+        .iter()
+        .enumerate()
+        .flat_map(|(row, rows)| rows.iter().enumerate().map(move |(col, t)| (row, col, t)))
+        .filter(|(_, _, tile)| matches!(tile.content, Content::Coin(_)))
+        .map(|(row, col, _)| (row, col))
+        .collect::<Vec<(usize, usize)>>();
+    assert!(coins.iter().any(|&(row, _)| row < 32));
+    assert!(coins.iter().any(|&(row, _)| row >= 32));
+    for (i, &(r1, c1)) in coins.iter().enumerate() {
+        for &(r2, c2) in &coins[i + 1..] {
+            assert!(r1.abs_diff(r2).pow(2) + c1.abs_diff(c2).pow(2) >= 16);
+        }
+    }
+}
+
+#[test]
+fn region_options_take_the_place_of_global_ones() {
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(3)
+        .set_size(64)
+        .set_with_info(false)
+        .set_content_options_from_preset(OxAgContentPresets::None)
+        .alter_content_option(
+            Content::Coin(0),
+            OxAgContentOptions {
+                is_present: true,
+                with_max_spawn_number: true,
+                max_spawn_number: 300,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .add_region_content_option(
+            OxAgRegion::DistanceFromSpawn(0..=20),
+            Content::Coin(0),
+            OxAgContentOptions {
+                exact_count: Some(5),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+    let (map, spawn, ..) = generator.gen();
+    let coins = map
+        .iter()
+        .enumerate()
+        .flat_map(|(row, rows)| rows.iter().enumerate().map(move |(col, t)| (row, col, t)))
+        .filter(|(_, _, tile)| matches!(tile.content, Content::Coin(_)))
+        .map(|(row, col, _)| {
+            ((row.abs_diff(spawn.0).pow(2) + col.abs_diff(spawn.1).pow(2)) as f64).sqrt()
+        })
+        .collect::<Vec<f64>>();
+    assert_eq!(
+        coins.iter().filter(|&&distance| distance <= 20.0).count(),
+        5
+    );
+    assert!(coins.len() > 5);
+    // A single entry for the global options and the region ones
+    let reports = generator
+        .get_placement_report()
+        .iter()
+        .filter(|report| report.content == Content::Coin(0))
+        .collect::<Vec<_>>();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].requested, 305);
+}
+
+#[test]
+fn placement_rules_are_followed() {
+    let rules = [
+        (
+            Content::Rock(0),
+            OxAgContentOptions {
+                exact_count: Some(30),
+                allowed_tile_types: vec![TileType::Hill],
+                ..Default::default()
+            },
+        ),
+        (
+            Content::Fire,
+            OxAgContentOptions {
+                exact_count: Some(30),
+                forbidden_neighbours: vec![TileType::ShallowWater, TileType::DeepWater],
+                neighbour_radius: 2,
+                ..Default::default()
+            },
+        ),
+        (
+            Content::Fish(0),
+            OxAgContentOptions {
+                exact_count: Some(10),
+                required_neighbours: vec![TileType::Sand],
+                ..Default::default()
+            },
+        ),
+    ];
+    // Without the fires of the volcanoes
+    let mut tile_type_options = OxAgTileTypePresets::Default.load();
+    tile_type_options.volcano_n = 0..=0;
+    let mut builder = OxAgWorldGeneratorBuilder::new()
+        .set_seed(3)
+        .set_size(64)
+        .set_with_info(false)
+        .set_tile_type_options(tile_type_options)
+        .unwrap()
+        .set_content_options_from_preset(OxAgContentPresets::None);
+    for (content, options) in rules {
+        builder = builder.alter_content_option(content, options).unwrap();
+    }
+    let map = builder.build().unwrap().try_generate().unwrap().0;
+    let size = map.len();
+    let around = |row: usize, col: usize, radius: usize| {
+        (row.saturating_sub(radius)..=(row + radius).min(size - 1))
+            .flat_map(move |r| {
+                (col.saturating_sub(radius)..=(col + radius).min(size - 1)).map(move |c| (r, c))
+            })
+            .filter(move |&tile| tile != (row, col))
+            .map(|(r, c)| map[r][c].tile_type)
+            .collect::<Vec<TileType>>()
+    };
+    let mut counts = (0, 0, 0);
+    for (row, tiles) in map.iter().enumerate() {
+        for (col, tile) in tiles.iter().enumerate() {
+            match tile.content {
+                Content::Rock(_) => {
+                    counts.0 += 1;
+                    assert_eq!(tile.tile_type, TileType::Hill);
+                }
+                Content::Fire => {
+                    counts.1 += 1;
+                    assert!(around(row, col, 2).iter().all(|tile_type| ![
+                        TileType::ShallowWater,
+                        TileType::DeepWater
+                    ]
+                    .contains(tile_type)));
+                }
+                Content::Fish(_) => {
+                    counts.2 += 1;
+                    assert!(around(row, col, 1).contains(&TileType::Sand));
+                }
+                _ => {}
+            }
+        }
+    }
+    assert_eq!(counts, (30, 30, 10));
+
+    // Rules that can never be satisfied are rejected
+    assert!(matches!(
+        OxAgWorldGeneratorBuilder::new().alter_content_option(
+            Content::Fish(0),
+            OxAgContentOptions {
+                allowed_tile_types: vec![TileType::Grass],
+                ..Default::default()
+            },
+        ),
+        Err(OxAgError::UnsatisfiablePlacementRule(Content::Fish(_)))
+    ));
+}
+
+#[test]
+fn exact_count_takes_away_the_extras() {
+    let mut tile_type_options = OxAgTileTypePresets::Default.load();
+    tile_type_options.settlement_n = 3..=3;
+    tile_type_options.settlement_density = 1.0;
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(3)
+        .set_size(128)
+        .set_with_info(false)
+        .set_tile_type_options(tile_type_options)
+        .unwrap()
+        .set_content_options_from_preset(OxAgContentPresets::None)
+        .alter_content_option(
+            Content::Building,
+            OxAgContentOptions {
+                exact_count: Some(3),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+    let map = generator.gen().0;
+    let buildings = map
+        .iter()
+        .flatten()
+        .filter(|tile| tile.content == Content::Building)
+        .count();
+    assert_eq!(buildings, 3);
+    let report = generator.get_placement_report().last().unwrap();
+    assert_eq!((report.requested, report.placed, report.failed), (3, 3, 0));
+    assert!(report.reasons.is_empty());
+}
+
+#[test]
+fn anchors_come_first_even_with_exact_counts() {
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(3)
+        .set_size(64)
+        .set_with_info(false)
+        .set_content_options_from_preset(OxAgContentPresets::None)
+        .alter_content_option(
+            Content::Scarecrow,
+            OxAgContentOptions {
+                is_present: true,
+                max_spawn_number: 10,
+                anchor: Some(OxAgAnchor {
+                    content: Content::Tree(0),
+                    distance: 1..=2,
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .alter_content_option(
+            Content::Tree(0),
+            OxAgContentOptions {
+                exact_count: Some(30),
+                priority: 5,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+    generator.gen();
+    let report = generator.get_placement_report();
+    assert_eq!(report[0].content, Content::Tree(0));
+    assert_eq!(report[1].content, Content::Scarecrow);
+    assert!(report[1].placed > 0);
+}
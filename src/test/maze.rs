@@ -0,0 +1,519 @@
+use robotics_lib::world::tile::{Content, Tile, TileType};
+use robotics_lib::world::world_generator::Generator;
+
+use crate::utils::errors::OxAgError;
+use crate::world_generator::content_options::OxAgContentOptions;
+use crate::world_generator::dungeon::{OxAgDungeonOptions, OxAgRoomShape};
+use crate::world_generator::maze_metrics::OxAgMazeDifficulty;
+use crate::world_generator::maze_options::OxAgMazeAlgorithm;
+use crate::world_generator::presets::content_presets::OxAgContentPresets;
+use crate::world_generator::world_generator_builder::OxAgWorldGeneratorBuilder;
+
+fn maze(algorithm: OxAgMazeAlgorithm) -> Vec<Vec<Tile>> {
+    braided_maze(algorithm, 0.0, 0)
+}
+
+fn braided_maze(algorithm: OxAgMazeAlgorithm, braid: f64, loops: usize) -> Vec<Vec<Tile>> {
+    OxAgWorldGeneratorBuilder::new()
+        .set_seed(9)
+        .set_size(41)
+        .set_with_info(false)
+        .set_maze(true)
+        .set_maze_algorithm(algorithm)
+        .set_maze_braid(braid)
+        .unwrap()
+        .set_maze_loops(loops)
+        .set_content_options_from_preset(OxAgContentPresets::None)
+        .build()
+        .unwrap()
+        .gen()
+        .0
+}
+
+// Number of path tiles reachable from the start
+fn reachable_paths(map: &[Vec<Tile>], start: (usize, usize)) -> usize {
+    let size = map.len();
+    let mut seen = vec![vec![false; size]; size];
+    let mut stack = vec![start];
+    seen[start.0][start.1] = true;
+    let mut count = 0;
+    while let Some((row, col)) = stack.pop() {
+        count += 1;
+        for (r, c) in [
+            (row - 1, col),
+            (row + 1, col),
+            (row, col - 1),
+            (row, col + 1),
+        ] {
+            if !seen[r][c] && map[r][c].tile_type != TileType::Wall {
+                seen[r][c] = true;
+                stack.push((r, c));
+            }
+        }
+    }
+    count
+}
+
+#[test]
+fn every_algorithm_builds_a_perfect_maze() {
+    for algorithm in [
+        OxAgMazeAlgorithm::RecursiveBacktracker,
+        OxAgMazeAlgorithm::Prim,
+        OxAgMazeAlgorithm::Kruskal,
+        OxAgMazeAlgorithm::Wilson,
+        OxAgMazeAlgorithm::Eller,
+        OxAgMazeAlgorithm::RecursiveDivision,
+    ] {
+        let map = maze(algorithm);
+        let cells = (map.len() - 1) / 2;
+        let paths = map
+            .iter()
+            .flatten()
+            .filter(|tile| tile.tile_type != TileType::Wall)
+            .count();
+        // Every cell is connected to the others, with exactly one path between any two of them
+        assert_eq!(paths, 2 * cells * cells - 1, "{:?}", algorithm);
+        assert_eq!(reachable_paths(&map, (1, 1)), paths, "{:?}", algorithm);
+    }
+}
+
+#[test]
+fn braiding_removes_dead_ends_and_adds_loops() {
+    let map = braided_maze(OxAgMazeAlgorithm::Kruskal, 1.0, 0);
+    let dead_ends = (1..map.len() - 1)
+        .step_by(2)
+        .flat_map(|row| (1..map.len() - 1).step_by(2).map(move |col| (row, col)))
+        .filter(|&(row, col)| {
+            [
+                (row - 1, col),
+                (row + 1, col),
+                (row, col - 1),
+                (row, col + 1),
+            ]
+            .iter()
+            .filter(|&&(r, c)| map[r][c].tile_type != TileType::Wall)
+            .count()
+                == 1
+        })
+        .count();
+    assert_eq!(dead_ends, 0);
+
+    let paths = |map: &[Vec<Tile>]| {
+        map.iter()
+            .flatten()
+            .filter(|tile| tile.tile_type != TileType::Wall)
+            .count()
+    };
+    let perfect = maze(OxAgMazeAlgorithm::Prim);
+    let looped = braided_maze(OxAgMazeAlgorithm::Prim, 0.0, 25);
+    assert_eq!(paths(&looped), paths(&perfect) + 25);
+
+    assert!(OxAgWorldGeneratorBuilder::new()
+        .set_maze_braid(1.5)
+        .is_err());
+}
+
+#[test]
+fn dungeon_rooms_are_connected() {
+    for room_shape in [OxAgRoomShape::Rectangular, OxAgRoomShape::Cave] {
+        let mut generator = OxAgWorldGeneratorBuilder::new()
+            .set_seed(4)
+            .set_size(64)
+            .set_with_info(false)
+            .set_dungeon(OxAgDungeonOptions {
+                room_shape,
+                ..Default::default()
+            })
+            .unwrap()
+            .set_content_options_from_preset(OxAgContentPresets::None)
+            .build()
+            .unwrap();
+        let (map, ..) = generator.gen();
+        let layout = generator.get_dungeon_layout().unwrap();
+        assert!(layout.rooms.len() > 1);
+        let paths = map
+            .iter()
+            .flatten()
+            .filter(|tile| tile.tile_type != TileType::Wall)
+            .count();
+        assert!(map
+            .iter()
+            .flatten()
+            .filter(|tile| tile.tile_type != TileType::Wall)
+            .all(|tile| tile.tile_type.properties().walk()));
+        let first = &layout.rooms[0];
+        let centre = (
+            (first.from.0 + first.to.0) / 2,
+            (first.from.1 + first.to.1) / 2,
+        );
+        assert_eq!(reachable_paths(&map, centre), paths);
+        assert!(!layout.doors.is_empty());
+        assert!(layout
+            .chokepoints
+            .iter()
+            .all(|chokepoint| layout.doors.contains(chokepoint)));
+    }
+
+    assert!(OxAgWorldGeneratorBuilder::new()
+        .set_maze(true)
+        .set_dungeon(OxAgDungeonOptions::default())
+        .unwrap()
+        .build()
+        .is_err());
+}
+
+#[test]
+fn maze_goal_is_the_farthest_tile() {
+    let solve = |percentile: f64| {
+        let mut generator = OxAgWorldGeneratorBuilder::new()
+            .set_seed(12)
+            .set_size(41)
+            .set_with_info(false)
+            .set_maze(true)
+            .set_maze_goal_percentile(percentile)
+            .unwrap()
+            .set_content_options_from_preset(OxAgContentPresets::None)
+            .build()
+            .unwrap();
+        let (map, spawn, ..) = generator.gen();
+        (map, spawn, generator.get_maze_solution().cloned().unwrap())
+    };
+    let (map, spawn, solution) = solve(1.0);
+    assert_eq!(solution.path.first(), Some(&spawn));
+    assert_eq!(solution.path.last(), Some(&solution.goal));
+    assert_eq!(solution.length, solution.path.len() - 1);
+    assert!(matches!(
+        map[solution.goal.0][solution.goal.1].content,
+        Content::JollyBlock(1)
+    ));
+    assert!(solution.path.windows(2).all(|step| {
+        step[0].0.abs_diff(step[1].0) + step[0].1.abs_diff(step[1].1) == 1
+            && map[step[1].0][step[1].1].tile_type.properties().walk()
+    }));
+
+    // No free walkable tile is farther from the spawn
+    let size = map.len();
+    let mut distances = vec![vec![usize::MAX; size]; size];
+    distances[spawn.0][spawn.1] = 0;
+    let mut queue = std::collections::VecDeque::from([spawn]);
+    while let Some((row, col)) = queue.pop_front() {
+        for (r, c) in [
+            (row - 1, col),
+            (row + 1, col),
+            (row, col - 1),
+            (row, col + 1),
+        ] {
+            if distances[r][c] == usize::MAX && map[r][c].tile_type.properties().walk() {
+                distances[r][c] = distances[row][col] + 1;
+                queue.push_back((r, c));
+            }
+        }
+    }
+    let farthest = (0..size)
+        .flat_map(|row| (0..size).map(move |col| (row, col)))
+        .filter(|&(row, col)| {
+            let tile = &map[row][col];
+            distances[row][col] != usize::MAX
+                && ((row, col) == solution.goal || tile.content == Content::None)
+                && tile
+                    .tile_type
+                    .properties()
+                    .can_hold(&Content::JollyBlock(1))
+        })
+        .map(|(row, col)| distances[row][col])
+        .max()
+        .unwrap();
+    assert_eq!(solution.length, farthest);
+
+    assert!(solve(0.5).2.length < solution.length);
+}
+
+#[test]
+fn maze_metrics_of_a_perfect_maze() {
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(3)
+        .set_size(41)
+        .set_with_info(false)
+        .set_maze(true)
+        .set_maze_algorithm(OxAgMazeAlgorithm::Kruskal)
+        .set_content_options_from_preset(OxAgContentPresets::None)
+        .build()
+        .unwrap();
+    let (map, ..) = generator.gen();
+    let metrics = generator.get_maze_metrics().cloned().unwrap();
+    assert_eq!(metrics.seed, 3);
+    assert_eq!(
+        metrics.solution_length,
+        generator.get_maze_solution().map(|s| s.length)
+    );
+
+    // A tree, split by the deep water: the corridors are its edges
+    // and every branch of a piece ends in a dead end
+    let size = map.len();
+    let walkable = |(row, col): (usize, usize)| map[row][col].tile_type.properties().walk();
+    let neighbours = |(row, col): (usize, usize)| {
+        [(1, 0), (0, 1)]
+            .into_iter()
+            .map(move |(dr, dc)| (row + dr, col + dc))
+            .chain([(row.wrapping_sub(1), col), (row, col.wrapping_sub(1))])
+            .filter(|&(r, c)| r < size && c < size && walkable((r, c)))
+    };
+    let (mut pieces, mut lone_tiles, mut edges) = (0, 0, 0);
+    let mut seen = vec![vec![false; size]; size];
+    for tile in (0..size).flat_map(|row| (0..size).map(move |col| (row, col))) {
+        if !walkable(tile) || seen[tile.0][tile.1] {
+            continue;
+        }
+        pieces += 1;
+        if neighbours(tile).count() == 0 {
+            lone_tiles += 1;
+        }
+        seen[tile.0][tile.1] = true;
+        let mut stack = vec![tile];
+        while let Some(current) = stack.pop() {
+            for (r, c) in neighbours(current) {
+                edges += 1;
+                if !seen[r][c] {
+                    seen[r][c] = true;
+                    stack.push((r, c));
+                }
+            }
+        }
+    }
+    assert_eq!(metrics.corridor_lengths.iter().sum::<usize>(), edges / 2);
+    let branches = metrics.decision_points as f64 * (metrics.branching_factor - 1.0);
+    let ends = 2.0 * (pieces - lone_tiles) as f64 + branches;
+    assert!((metrics.dead_ends as f64 - ends).abs() < 1e-6);
+    assert!(metrics.corridor_lengths.windows(2).all(|w| w[0] <= w[1]));
+
+    // Braiding opens every dead end, only the water can close the paths
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(3)
+        .set_size(41)
+        .set_with_info(false)
+        .set_maze(true)
+        .set_maze_braid(1.0)
+        .unwrap()
+        .set_content_options_from_preset(OxAgContentPresets::None)
+        .build()
+        .unwrap();
+    let (map, ..) = generator.gen();
+    let size = map.len();
+    let dead_ends = (1..size - 1)
+        .flat_map(|row| (1..size - 1).map(move |col| (row, col)))
+        .filter(|&(row, col)| {
+            let around = [
+                (row - 1, col),
+                (row + 1, col),
+                (row, col - 1),
+                (row, col + 1),
+            ];
+            map[row][col].tile_type.properties().walk()
+                && around
+                    .iter()
+                    .filter(|&&(r, c)| map[r][c].tile_type.properties().walk())
+                    .count()
+                    == 1
+                && !around.iter().any(|&(r, c)| {
+                    map[r][c].tile_type != TileType::Wall
+                        && !map[r][c].tile_type.properties().walk()
+                })
+        })
+        .count();
+    assert!(generator.get_maze_metrics().unwrap().dead_ends > 0);
+    assert_eq!(dead_ends, 0);
+}
+
+#[test]
+fn maze_difficulty_is_reached_or_reported() {
+    for difficulty in [
+        OxAgMazeDifficulty::Easy,
+        OxAgMazeDifficulty::Medium,
+        OxAgMazeDifficulty::Hard,
+    ] {
+        let mut generator = OxAgWorldGeneratorBuilder::new()
+            .set_seed(5)
+            .set_size(41)
+            .set_with_info(false)
+            .set_maze(true)
+            .set_maze_difficulty(difficulty.clone())
+            .unwrap()
+            .set_content_options_from_preset(OxAgContentPresets::None)
+            .build()
+            .unwrap();
+        let world = generator.try_generate();
+        let metrics = generator.get_maze_metrics().cloned().unwrap();
+        let paths = generator
+            .gen()
+            .0
+            .iter()
+            .flatten()
+            .filter(|tile| tile.tile_type.properties().walk())
+            .count();
+        let share = metrics.dead_ends as f64 / paths as f64;
+        let reached = metrics
+            .tortuosity
+            .is_some_and(|tortuosity| match difficulty {
+                OxAgMazeDifficulty::Easy => tortuosity <= 2.0 && share <= 0.1,
+                OxAgMazeDifficulty::Medium => {
+                    (2.0..=4.0).contains(&tortuosity) && (0.05..=0.25).contains(&share)
+                }
+                _ => tortuosity >= 4.0 && share >= 0.05,
+            });
+        assert_eq!(
+            reached,
+            !matches!(world, Err(OxAgError::MazeDifficultyNotReached))
+        );
+    }
+    assert!(OxAgWorldGeneratorBuilder::new()
+        .set_maze_difficulty(OxAgMazeDifficulty::Custom {
+            tortuosity: 0.5..=2.0,
+            dead_end_share: 0.0..=1.0,
+        })
+        .is_err());
+}
+
+#[test]
+fn maze_contents_follow_the_corridors() {
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(6)
+        .set_size(41)
+        .set_with_info(false)
+        .set_maze(true)
+        .set_height_multiplier(20.0)
+        .set_content_options_from_preset(OxAgContentPresets::None)
+        .alter_content_option(
+            Content::Rock(0),
+            OxAgContentOptions {
+                in_batches: true,
+                is_present: true,
+                max_radius: 3,
+                max_spawn_number: 10,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .alter_content_option(
+            Content::Coin(0),
+            OxAgContentOptions {
+                exact_count: Some(5),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+    let (map, ..) = generator.gen();
+    let size = map.len();
+    let paths_around = |row: usize, col: usize| {
+        [
+            (row - 1, col),
+            (row + 1, col),
+            (row, col - 1),
+            (row, col + 1),
+        ]
+        .into_iter()
+        .filter(|&(r, c)| r < size && c < size && map[r][c].tile_type != TileType::Wall)
+        .count()
+    };
+    let (mut rocks, mut coins) = (0, 0);
+    for (row, tiles) in map.iter().enumerate() {
+        for (col, tile) in tiles.iter().enumerate() {
+            match tile.content {
+                Content::Rock(_) => {
+                    rocks += 1;
+                    assert_eq!(paths_around(row, col), 2);
+                }
+                Content::Coin(_) => {
+                    coins += 1;
+                    assert_eq!(paths_around(row, col), 1);
+                }
+                _ => {}
+            }
+        }
+    }
+    assert!(rocks > 0);
+    assert_eq!(coins, 5);
+
+    // The elevation comes from the noise
+    let elevations = map
+        .iter()
+        .flatten()
+        .map(|tile| tile.elevation)
+        .collect::<Vec<usize>>();
+    assert!(elevations.iter().all(|&elevation| elevation <= 40));
+    assert!(elevations.iter().min() < elevations.iter().max());
+}
+
+#[test]
+fn maze_spawn_is_always_walkable() {
+    // The paths of some small mazes are all water
+    for seed in 15..25 {
+        let mut generator = OxAgWorldGeneratorBuilder::new()
+            .set_seed(seed)
+            .set_size(21)
+            .set_maze(true)
+            .set_with_info(false)
+            .build()
+            .unwrap();
+        let (map, (row, col), ..) = generator.gen();
+        assert!(map[row][col].tile_type.properties().walk());
+    }
+}
+
+#[test]
+fn maze_goal_keeps_the_exact_counts() {
+    for seed in [2, 3, 5, 6, 7] {
+        let mut generator = OxAgWorldGeneratorBuilder::new()
+            .set_seed(seed)
+            .set_size(41)
+            .set_with_info(false)
+            .set_maze(true)
+            .set_height_multiplier(20.0)
+            .set_content_options_from_preset(OxAgContentPresets::None)
+            .alter_content_option(
+                Content::Coin(0),
+                OxAgContentOptions {
+                    exact_count: Some(5),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+        let (map, ..) = generator.gen();
+        let count = |content: Content| {
+            map.iter()
+                .flatten()
+                .filter(|tile| tile.content.to_default() == content)
+                .count()
+        };
+        assert_eq!(count(Content::Coin(0)), 5);
+        assert_eq!(count(Content::JollyBlock(0)), 1);
+        let report = generator.get_placement_report().last().unwrap();
+        assert_eq!((report.requested, report.placed), (5, 5));
+        let goal = generator.get_maze_solution().unwrap().goal;
+        assert!(matches!(
+            map[goal.0][goal.1].content,
+            Content::JollyBlock(1)
+        ));
+    }
+}
+
+#[test]
+fn maze_goal_is_reported_when_it_cant_be_placed() {
+    // The spawn of this maze is walled in by deep water
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(1)
+        .set_size(11)
+        .set_with_info(false)
+        .set_maze(true)
+        .set_content_options_from_preset(OxAgContentPresets::None)
+        .build()
+        .unwrap();
+    assert!(matches!(
+        generator.try_generate(),
+        Err(OxAgError::MazeGoalPlacementFailed)
+    ));
+    assert!(generator.get_maze_solution().is_none());
+}
@@ -1,3 +1,5 @@
 mod content;
+mod maze;
 mod others;
+mod shapes;
 mod tile;
@@ -1,4 +1,342 @@
+use robotics_lib::world::tile::{Content, Tile, TileType};
+use robotics_lib::world::world_generator::Generator;
+
+use crate::utils::errors::OxAgError;
+use crate::world_generator::connectivity::OxAgConnectivityOptions;
+use crate::world_generator::content_options::OxAgContentOptions;
+use crate::world_generator::maze_metrics::OxAgMazeMetrics;
+use crate::world_generator::multi_spawn::{OxAgMultiSpawnOptions, OxAgSpawnFairness};
+use crate::world_generator::presets::content_presets::OxAgContentPresets;
+use crate::world_generator::regions::OxAgRegion;
+use crate::world_generator::spawn_strategy::OxAgSpawnStrategy;
+use crate::world_generator::spawning_tools::connectivity::{reachable, WalkableRegions};
+use crate::world_generator::spawning_tools::multi_spawn::resources_around;
+use crate::world_generator::teleports::OxAgTeleportOptions;
+use crate::world_generator::world_generator_builder::OxAgWorldGeneratorBuilder;
+
 #[test]
 fn t() {
     assert_eq!(2, 2);
 }
+
+#[test]
+fn spawn_is_in_the_largest_region() {
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(7)
+        .set_size(64)
+        .set_with_info(false)
+        .set_content_options_from_preset(OxAgContentPresets::None)
+        .set_spawn_strategy(OxAgSpawnStrategy::LargestRegion)
+        .build()
+        .unwrap();
+    let (map, spawn, ..) = generator.gen();
+    let regions = WalkableRegions::new(&map);
+    let largest = regions.sizes.iter().max().copied().unwrap();
+    assert_eq!(regions.size_of(spawn.0, spawn.1), largest);
+    let report = generator.get_spawn_report().unwrap();
+    assert!(report.satisfied);
+    assert_eq!(report.region_size, largest);
+}
+
+#[test]
+fn spawn_reaches_enough_resources() {
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(11)
+        .set_size(64)
+        .set_with_info(false)
+        .set_content_options_from_preset(OxAgContentPresets::Default)
+        .set_spawn_strategy(OxAgSpawnStrategy::Resources {
+            content: Content::Tree(0),
+            count: 5,
+            max_steps: 10,
+        })
+        .build()
+        .unwrap();
+    let (_, spawn, ..) = generator.gen();
+    let report = generator.get_spawn_report().unwrap();
+    assert_eq!(report.spawn, spawn);
+    assert!(report.satisfied);
+    assert!(report.reachable_resources.unwrap() >= 5);
+}
+
+#[test]
+fn spawn_reaches_the_most_resources_when_not_enough() {
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(11)
+        .set_size(100)
+        .set_with_info(false)
+        .set_content_options_from_preset(OxAgContentPresets::Default)
+        .set_spawn_strategy(OxAgSpawnStrategy::Resources {
+            content: Content::Tree(0),
+            count: 10000,
+            max_steps: 3,
+        })
+        .build()
+        .unwrap();
+    let (map, spawn, ..) = generator.gen();
+    let most = (0..100)
+        .flat_map(|row| (0..100).map(move |col| (row, col)))
+        .filter(|&(row, col)| map[row][col].tile_type.properties().walk())
+        .map(|start| {
+            reachable(&map, start, 3)
+                .into_iter()
+                .filter(|&((row, col), _)| matches!(map[row][col].content, Content::Tree(_)))
+                .count()
+        })
+        .max()
+        .unwrap();
+    let report = generator.get_spawn_report().unwrap();
+    assert_eq!(report.spawn, spawn);
+    assert!(!report.satisfied);
+    assert_eq!(report.reachable_resources, Some(most));
+}
+
+#[test]
+fn multiple_spawns_follow_their_options() {
+    let fairness = OxAgSpawnFairness {
+        radius: 8,
+        resources: vec![Content::Tree(0), Content::Rock(0)],
+        tolerance: 15,
+    };
+    let (map, spawns, ..) = OxAgWorldGeneratorBuilder::new()
+        .set_seed(5)
+        .set_size(80)
+        .set_with_info(false)
+        .set_content_options_from_preset(OxAgContentPresets::Default)
+        .build()
+        .unwrap()
+        .generate_with_spawns(&OxAgMultiSpawnOptions {
+            count: 4,
+            min_distance: 15,
+            same_region: true,
+            fairness: Some(fairness.clone()),
+        })
+        .unwrap();
+    assert_eq!(spawns.len(), 4);
+    let regions = WalkableRegions::new(&map);
+    for (i, a) in spawns.iter().enumerate() {
+        assert_eq!(
+            regions.labels[a.0][a.1],
+            regions.labels[spawns[0].0][spawns[0].1]
+        );
+        for b in spawns.iter().skip(i + 1) {
+            assert!(((a.0.abs_diff(b.0).pow(2) + a.1.abs_diff(b.1).pow(2)) as f64).sqrt() >= 15.0);
+        }
+    }
+    let counts = spawns
+        .iter()
+        .map(|&spawn| resources_around(&map, spawn, &fairness))
+        .collect::<Vec<usize>>();
+    assert!(counts.iter().max().unwrap() - counts.iter().min().unwrap() <= 15);
+}
+
+#[test]
+fn multiple_spawns_never_share_a_tile() {
+    let builder = || {
+        OxAgWorldGeneratorBuilder::new()
+            .set_seed(5)
+            .set_size(16)
+            .set_with_info(false)
+            .set_content_options_from_preset(OxAgContentPresets::None)
+    };
+    let (map, first, ..) = builder().build().unwrap().gen();
+    let regions = WalkableRegions::new(&map);
+    let count = regions.size_of(first.0, first.1);
+    let (_, spawns, ..) = builder()
+        .build()
+        .unwrap()
+        .generate_with_spawns(&OxAgMultiSpawnOptions {
+            count,
+            min_distance: 0,
+            same_region: true,
+            fairness: None,
+        })
+        .unwrap();
+    let mut unique = spawns.clone();
+    unique.sort_unstable();
+    unique.dedup();
+    assert_eq!(spawns.len(), count);
+    assert_eq!(unique.len(), count);
+}
+
+#[test]
+fn repair_connects_every_walkable_tile() {
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(2)
+        .set_size(64)
+        .set_with_info(false)
+        .set_content_options_from_preset(OxAgContentPresets::None)
+        .set_connectivity_options(OxAgConnectivityOptions {
+            min_reachable: 1.0,
+            max_paths: usize::MAX,
+            ..Default::default()
+        })
+        .unwrap()
+        .build()
+        .unwrap();
+    let (map, spawn, ..) = generator.gen();
+    let regions = WalkableRegions::new(&map);
+    let report = generator.get_connectivity_report().unwrap();
+    assert!(report.components.len() > 1);
+    assert!(report.carved > 0);
+    assert_eq!(regions.sizes.len(), 1);
+    assert_eq!(regions.size_of(spawn.0, spawn.1), report.walkable);
+    assert_eq!(report.reachable_after, report.walkable);
+
+    assert!(OxAgWorldGeneratorBuilder::new()
+        .set_connectivity_options(OxAgConnectivityOptions {
+            path_tile_type: TileType::DeepWater,
+            ..Default::default()
+        })
+        .is_err());
+}
+
+#[test]
+fn repair_goes_for_the_big_regions_and_keeps_the_walls() {
+    let generator = |seed, maze, connectivity_options: Option<OxAgConnectivityOptions>| {
+        let builder = OxAgWorldGeneratorBuilder::new()
+            .set_seed(seed)
+            .set_size(41)
+            .set_with_info(false)
+            .set_maze(maze)
+            .set_content_options_from_preset(OxAgContentPresets::None);
+        match connectivity_options {
+            Some(options) => builder.set_connectivity_options(options).unwrap(),
+            None => builder,
+        }
+        .build()
+        .unwrap()
+    };
+    let walls = |map: &Vec<Vec<Tile>>| {
+        map.iter()
+            .flatten()
+            .filter(|tile| tile.tile_type == TileType::Wall)
+            .count()
+    };
+    let repair = OxAgConnectivityOptions {
+        min_reachable: 1.0,
+        max_paths: usize::MAX,
+        ..Default::default()
+    };
+    let maze = generator(2, true, None).gen().0;
+    let repaired = generator(2, true, Some(repair)).gen().0;
+    assert_eq!(walls(&repaired), walls(&maze));
+
+    // The single path doesn't go for the lone tile next to the spawn region
+    let mut generator = generator(
+        20,
+        false,
+        Some(OxAgConnectivityOptions {
+            min_reachable: 1.0,
+            max_paths: 1,
+            ..Default::default()
+        }),
+    );
+    generator.gen();
+    let report = generator.get_connectivity_report().unwrap();
+    assert!(report.reachable_after - report.reachable_before > report.carved + 1);
+}
+
+#[test]
+fn teleports_are_paired_and_cleared() {
+    for maze in [false, true] {
+        let mut generator = OxAgWorldGeneratorBuilder::new()
+            .set_seed(4)
+            .set_size(65)
+            .set_with_info(false)
+            .set_maze(maze)
+            .set_content_options_from_preset(OxAgContentPresets::None)
+            .set_teleports(OxAgTeleportOptions {
+                pairs: 3,
+                min_distance: 12,
+                clearance: 3,
+            })
+            .unwrap()
+            .build()
+            .unwrap();
+        let (map, ..) = generator.gen();
+        let report = generator.get_teleport_report().cloned().unwrap();
+        assert_eq!(report.pairs.len(), 3);
+        let teleports = report
+            .pairs
+            .iter()
+            .flat_map(|pair| pair.ends)
+            .collect::<Vec<(usize, usize)>>();
+        let sector = |(row, col): (usize, usize)| (row * 3 / 65, col * 3 / 65);
+        for (i, &(row, col)) in teleports.iter().enumerate() {
+            assert!(matches!(map[row][col].tile_type, TileType::Teleport(false)));
+            for &other in teleports.iter().skip(i + 1) {
+                assert_ne!(sector((row, col)), sector(other));
+                assert!(row.abs_diff(other.0) + col.abs_diff(other.1) >= 12);
+            }
+            for r in row.saturating_sub(3)..=(row + 3).min(64) {
+                for c in col.saturating_sub(3)..=(col + 3).min(64) {
+                    let tile_type = map[r][c].tile_type;
+                    assert!(tile_type.properties().walk() || tile_type == TileType::Wall);
+                }
+            }
+        }
+        assert!(report.reachable >= report.reachable_walking);
+        assert_eq!(
+            map.iter()
+                .flatten()
+                .filter(|tile| matches!(tile.tile_type, TileType::Teleport(_)))
+                .count(),
+            6
+        );
+
+        // The maze is measured once the teleports cleared their surroundings
+        if maze {
+            assert!(report.cleared > 0);
+            let metrics = generator.get_maze_metrics().unwrap();
+            assert_eq!(
+                &OxAgMazeMetrics::new(&map, 4, generator.get_maze_solution()),
+                metrics
+            );
+        }
+    }
+    assert!(OxAgWorldGeneratorBuilder::new()
+        .set_teleports(OxAgTeleportOptions {
+            pairs: 0,
+            ..Default::default()
+        })
+        .is_err());
+}
+
+#[test]
+fn spawn_strategies_moving_the_spawn_are_not_combined() {
+    let resources = OxAgSpawnStrategy::Resources {
+        content: Content::Tree(0),
+        count: 5,
+        max_steps: 10,
+    };
+    assert!(matches!(
+        OxAgWorldGeneratorBuilder::new()
+            .set_spawn_strategy(resources.clone())
+            .set_connectivity_options(OxAgConnectivityOptions::default())
+            .unwrap()
+            .build(),
+        Err(OxAgError::IncompatibleSpawnStrategy)
+    ));
+    assert!(matches!(
+        OxAgWorldGeneratorBuilder::new()
+            .set_content_options_from_preset(OxAgContentPresets::Default)
+            .add_region_content_option(
+                OxAgRegion::DistanceFromSpawn(0..=20),
+                Content::Coin(0),
+                OxAgContentOptions::default(),
+            )
+            .unwrap()
+            .set_spawn_strategy(resources.clone())
+            .build(),
+        Err(OxAgError::IncompatibleSpawnStrategy)
+    ));
+    assert!(matches!(
+        OxAgWorldGeneratorBuilder::new()
+            .set_spawn_strategy(resources)
+            .set_teleports(OxAgTeleportOptions::default())
+            .unwrap()
+            .build(),
+        Err(OxAgError::IncompatibleSpawnStrategy)
+    ));
+}
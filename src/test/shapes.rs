@@ -0,0 +1,83 @@
+use robotics_lib::world::tile::{Content, Tile, TileType};
+
+use crate::world_generator::shapes::OxAgShape;
+
+fn grass(size: usize) -> Vec<Vec<Tile>> {
+    vec![
+        vec![
+            Tile {
+                tile_type: TileType::Grass,
+                content: Content::None,
+                elevation: 0,
+            };
+            size
+        ];
+        size
+    ]
+}
+
+#[test]
+fn line_is_continuous() {
+    let cells = OxAgShape::Line {
+        from: (0, 0),
+        to: (9, 4),
+    }
+    .cells(10);
+    assert_eq!(cells.len(), 10);
+    assert!(cells
+        .windows(2)
+        .all(|w| w[0].0.abs_diff(w[1].0) <= 1 && w[0].1.abs_diff(w[1].1) <= 1));
+}
+
+#[test]
+fn shapes_are_clipped_to_the_map() {
+    let cells = OxAgShape::Circle {
+        centre: (0, 0),
+        radius: 3,
+        filled: true,
+    }
+    .cells(10);
+    assert!(cells.contains(&(0, 0)));
+    assert!(cells.iter().all(|&(r, c)| r < 10 && c < 10));
+}
+
+#[test]
+fn ring_leaves_the_hole_untouched() {
+    let mut map = grass(11);
+    OxAgShape::Ring {
+        centre: (5, 5),
+        inner_radius: 2,
+        outer_radius: 4,
+    }
+    .stamp(
+        &mut map,
+        &mut rand::thread_rng(),
+        &(None, Some(TileType::Lava)),
+    );
+    assert_eq!(map[5][5].tile_type, TileType::Grass);
+    assert_eq!(map[5][9].tile_type, TileType::Lava);
+    assert_eq!(map[5][10].tile_type, TileType::Grass);
+}
+
+#[test]
+fn rectangle_outline_is_hollow() {
+    let cells = OxAgShape::Rectangle {
+        from: (2, 2),
+        to: (5, 6),
+        filled: false,
+    }
+    .cells(10);
+    assert_eq!(cells.len(), 14);
+    assert!(!cells.contains(&(3, 3)));
+}
+
+#[test]
+fn filled_polygon_contains_its_inside() {
+    let cells = OxAgShape::Polygon {
+        vertices: vec![(1, 1), (1, 8), (8, 8), (8, 1)],
+        filled: true,
+    }
+    .cells(10);
+    assert!(cells.contains(&(4, 4)));
+    assert!(!cells.contains(&(0, 0)));
+}
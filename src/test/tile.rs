@@ -1,4 +1,307 @@
+use robotics_lib::world::tile::{Content, Tile, TileType};
+use robotics_lib::world::world_generator::Generator;
+
+use crate::utils::errors::OxAgError;
+use crate::utils::traits::{FromSeed, Loadable, Validator};
+use crate::world_generator::content_options::{OxAgCapacityOptions, OxAgContentOptions};
+use crate::world_generator::presets::content_presets::OxAgContentPresets;
+use crate::world_generator::presets::tile_type_presets::OxAgTileTypePresets;
+use crate::world_generator::tile_type_options::OxAgTileTypeOptions;
+use crate::world_generator::world_generator_builder::OxAgWorldGeneratorBuilder;
+
 #[test]
 fn t() {
     assert_eq!(2, 2);
 }
+
+#[test]
+fn settlements_face_their_streets() {
+    let mut tile_type_options = OxAgTileTypePresets::Settled.load();
+    tile_type_options.settlement_n = 3..=3;
+    tile_type_options.settlement_radius = 8..=8;
+    tile_type_options.settlement_density = 1.0;
+    let mut generator = OxAgWorldGeneratorBuilder::new()
+        .set_seed(3)
+        .set_size(128)
+        .set_with_info(false)
+        .set_tile_type_options(tile_type_options)
+        .unwrap()
+        .set_content_options_from_preset(OxAgContentPresets::None)
+        .alter_content_option(
+            Content::Bin(0..0),
+            OxAgContentOptions {
+                capacity: Some(OxAgCapacityOptions {
+                    capacity_range: 5..=5,
+                    initial_fill: 0.5..=0.5,
+                    distribution: Default::default(),
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+    let map = generator.gen().0;
+    let size = map.len();
+    let count = |content: Content| {
+        map.iter()
+            .flatten()
+            .filter(|tile| tile.content.to_default() == content)
+            .count()
+    };
+    assert!(count(Content::Building) > 0);
+    assert!(count(Content::Market(0)) > 0);
+    assert!(count(Content::Bank(0..0)) > 0);
+    for (row, tiles) in map.iter().enumerate() {
+        for (col, tile) in tiles.iter().enumerate() {
+            match &tile.content {
+                Content::Building | Content::Market(_) | Content::Bank(_) => {
+                    assert!([TileType::Grass, TileType::Sand].contains(&tile.tile_type));
+                    assert!([
+                        (row.wrapping_sub(1), col),
+                        (row + 1, col),
+                        (row, col.wrapping_sub(1)),
+                        (row, col + 1),
+                    ]
+                    .iter()
+                    .any(|&(r, c)| r < size
+                        && c < size
+                        && map[r][c].tile_type == TileType::Street));
+                }
+                // The bins of the settlements follow the capacity options too
+                Content::Bin(range) => assert_eq!(*range, 3..5),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[test]
+fn settlements_and_volcanoes_are_opted_in() {
+    for preset in [
+        OxAgTileTypePresets::Default,
+        OxAgTileTypePresets::WaterWorld,
+        OxAgTileTypePresets::LowWaterWorld,
+        OxAgTileTypePresets::Hill,
+    ] {
+        assert_eq!(preset.load().settlement_n, 0..=0);
+        assert_eq!(preset.load().volcano_n, 0..=0);
+    }
+    assert!(!OxAgTileTypePresets::Settled
+        .load()
+        .settlement_n
+        .contains(&0));
+    assert!(!OxAgTileTypePresets::Volcanic.load().volcano_n.contains(&0));
+}
+
+/// Tiles 4-connected to `start` whose tile type is `tile_type`
+fn component(map: &[Vec<Tile>], start: (usize, usize), tile_type: TileType) -> usize {
+    let size = map.len();
+    let mut seen = vec![vec![false; size]; size];
+    let mut stack = vec![start];
+    seen[start.0][start.1] = true;
+    let mut count = 0;
+    while let Some((row, col)) = stack.pop() {
+        count += 1;
+        for (r, c) in [
+            (row.wrapping_sub(1), col),
+            (row + 1, col),
+            (row, col.wrapping_sub(1)),
+            (row, col + 1),
+        ] {
+            if r < size && c < size && !seen[r][c] && map[r][c].tile_type == tile_type {
+                seen[r][c] = true;
+                stack.push((r, c));
+            }
+        }
+    }
+    count
+}
+
+#[test]
+fn streets_stay_connected_across_rivers() {
+    for seed in 0..5 {
+        let mut tile_type_options = OxAgTileTypePresets::Default.load();
+        tile_type_options.settlement_n = 0..=0;
+        tile_type_options.volcano_n = 0..=0;
+        tile_type_options.street_n = 1..=1;
+        tile_type_options.street_len = 300..=300;
+        tile_type_options.river_n = 10..=10;
+        let mut generator = OxAgWorldGeneratorBuilder::new()
+            .set_seed(seed)
+            .set_size(128)
+            .set_with_info(false)
+            .set_tile_type_options(tile_type_options)
+            .unwrap()
+            .set_content_options_from_preset(OxAgContentPresets::None)
+            .build()
+            .unwrap();
+        let map = generator.gen().0;
+        let streets = map
+            .iter()
+            .enumerate()
+            .flat_map(|(row, tiles)| {
+                tiles
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, tile)| tile.tile_type == TileType::Street)
+                    .map(move |(col, _)| (row, col))
+            })
+            .collect::<Vec<(usize, usize)>>();
+        // A single street, bridges included, is a single road
+        assert!(streets.len() > 1);
+        assert_eq!(component(&map, streets[0], TileType::Street), streets.len());
+    }
+}
+
+#[test]
+fn volcanoes_follow_their_flow_options() {
+    let lava = |flows| {
+        let mut tile_type_options = OxAgTileTypePresets::Volcanic.load();
+        tile_type_options.lava_n = 0..=0;
+        tile_type_options.river_n = 0..=0;
+        tile_type_options.street_n = 0..=0;
+        tile_type_options.volcano_n = 1..=1;
+        tile_type_options.volcano_flows = flows;
+        tile_type_options.volcano_flow_len = 20..=20;
+        let mut generator = OxAgWorldGeneratorBuilder::new()
+            .set_seed(3)
+            .set_size(128)
+            .set_with_info(false)
+            .set_tile_type_options(tile_type_options)
+            .unwrap()
+            .set_content_options_from_preset(OxAgContentPresets::None)
+            .build()
+            .unwrap();
+        let map = generator.gen().0;
+        let size = map.len();
+        let is_lava = |row: usize, col: usize| {
+            row < size && col < size && map[row][col].tile_type == TileType::Lava
+        };
+        // Without fire in the content options, every fire comes from a flow
+        for (row, tiles) in map.iter().enumerate() {
+            for (col, tile) in tiles.iter().enumerate() {
+                if tile.content == Content::Fire {
+                    assert!((row.saturating_sub(1)..=row + 1)
+                        .any(|r| (col.saturating_sub(1)..=col + 1).any(|c| is_lava(r, c))));
+                }
+            }
+        }
+        let count = |tile_type: TileType| {
+            map.iter()
+                .flatten()
+                .filter(|tile| tile.tile_type == tile_type)
+                .count()
+        };
+        (count(TileType::Lava), count(TileType::Mountain))
+    };
+    let (crater, mountains) = lava(0..=0);
+    assert!(crater > 0);
+    let (flows, burned_mountains) = lava(3..=3);
+    assert!(flows > crater);
+    // The burned ground around the flows stays walkable
+    assert!(burned_mountains <= mountains);
+}
+
+#[test]
+fn lava_lakes_follow_their_options() {
+    let options = |lava_n, lava_host_tiles| {
+        let mut tile_type_options = OxAgTileTypePresets::Default.load();
+        tile_type_options.lava_n = lava_n;
+        tile_type_options.lava_host_tiles = lava_host_tiles;
+        tile_type_options
+    };
+    let generator = |lava_n, lava_host_tiles| {
+        OxAgWorldGeneratorBuilder::new()
+            .set_seed(3)
+            .set_size(64)
+            .set_with_info(false)
+            .set_tile_type_options(options(lava_n, lava_host_tiles))
+            .unwrap()
+            .set_content_options_from_preset(OxAgContentPresets::None)
+            .build()
+            .unwrap()
+    };
+    let map = generator(0..=0, vec![TileType::Grass, TileType::Hill])
+        .gen()
+        .0;
+    assert!(map
+        .iter()
+        .flatten()
+        .all(|tile| tile.tile_type != TileType::Lava));
+    // Without host tiles on the map the attempts run out instead of hanging
+    assert!(matches!(
+        generator(2..=2, vec![TileType::Wall]).try_generate(),
+        Err(OxAgError::LavaPlacementFailed(2, 0))
+    ));
+    assert!(matches!(
+        options(2..=2, vec![]).validate(),
+        Err(OxAgError::InvalidLavaOptions)
+    ));
+    let mut no_attempts = options(2..=2, vec![TileType::Grass]);
+    no_attempts.lava_attempts = 0;
+    assert!(matches!(
+        no_attempts.validate(),
+        Err(OxAgError::InvalidLavaOptions)
+    ));
+}
+
+#[test]
+fn new_tile_type_options_are_validated() {
+    let invalid = |change: &dyn Fn(&mut OxAgTileTypeOptions)| {
+        let mut tile_type_options = OxAgTileTypePresets::Default.load();
+        change(&mut tile_type_options);
+        tile_type_options.validate().unwrap_err()
+    };
+    assert!(matches!(
+        invalid(&|options| {
+            options.settlement_n = 1..=1;
+            options.settlement_radius = 0..=4;
+        }),
+        OxAgError::InvalidSettlementRadius
+    ));
+    assert!(matches!(
+        invalid(&|options| options.bridge_max_len = 16),
+        OxAgError::InvalidBridgeLength
+    ));
+    #[allow(clippy::reversed_empty_ranges)]
+    let empty = 3..=1;
+    assert!(matches!(
+        invalid(&|options| options.volcano_flow_len = empty.clone()),
+        OxAgError::InvalidVolcanoOptions
+    ));
+    assert!(OxAgTileTypePresets::Hill.load().validate().is_ok());
+    for seed in 0..50 {
+        assert!(OxAgTileTypeOptions::new_from_seed(seed).validate().is_ok());
+    }
+}
+
+#[test]
+fn spawn_keeps_away_from_lava() {
+    for seed in 0..5 {
+        let mut tile_type_options = OxAgTileTypePresets::Default.load();
+        tile_type_options.volcano_n = 0..=0;
+        tile_type_options.lava_n = 3..=3;
+        tile_type_options.lava_radius = 3..=3;
+        tile_type_options.lava_spawn_distance = 10;
+        let mut generator = OxAgWorldGeneratorBuilder::new()
+            .set_seed(seed)
+            .set_size(128)
+            .set_with_info(false)
+            .set_tile_type_options(tile_type_options)
+            .unwrap()
+            .set_content_options_from_preset(OxAgContentPresets::None)
+            .build()
+            .unwrap();
+        let (map, (row, col), ..) = generator.gen();
+        for (r, tiles) in map.iter().enumerate() {
+            for (c, tile) in tiles.iter().enumerate() {
+                if tile.tile_type == TileType::Lava {
+                    let distance =
+                        ((r as f64 - row as f64).powi(2) + (c as f64 - col as f64).powi(2)).sqrt();
+                    assert!(distance >= 10.0);
+                }
+            }
+        }
+    }
+}
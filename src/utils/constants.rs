@@ -31,5 +31,37 @@ pub const DEFAULT_NOISE_OCTAVES: usize = 12;
 pub const DEFAULT_SCORE: f32 = 100.0;
 pub const CONTENT_PERCENTAGE_RANGE: RangeInclusive<f64> = 0.0..=1.0;
 pub const DEFAULT_BATCH_DISTANCE: usize = 12;
+/// Probability for a tile inside a batch to hold the content, for the shapes without falloff
+pub const DEFAULT_BATCH_DENSITY: f64 = 0.7;
 
 pub const SAME_DIR_PROBABILITY: f64 = 0.8;
+/// Number of random centres tried for every lava lake before reporting the failure
+pub const DEFAULT_LAVA_ATTEMPTS: usize = 1000;
+/// Number of random tiles tried when looking for a spawn point
+pub const DEFAULT_SPAWN_ATTEMPTS: usize = 10000;
+/// Number of sets of spawn points tried before reporting that they can't follow the options
+pub const DEFAULT_MULTI_SPAWN_ATTEMPTS: usize = 100;
+/// Number of random rooms tried for every room of a dungeon
+pub const DEFAULT_ROOM_ATTEMPTS: usize = 50;
+/// Probability for a tile of a themed room to hold the content of its theme
+pub const DEFAULT_ROOM_THEME_DENSITY: f64 = 0.15;
+/// Mazes generated to reach the requested difficulty before the last one is kept
+pub const DEFAULT_MAZE_DIFFICULTY_ATTEMPTS: usize = 20;
+
+// settlement default values
+/// Distance between two parallel streets of a settlement
+pub const DEFAULT_SETTLEMENT_BLOCK_SIZE: usize = 4;
+/// Maximum distance, past its radius, between a settlement and the nearest water tile
+pub const DEFAULT_SETTLEMENT_WATER_DISTANCE: usize = 6;
+/// Minimum share of flat tiles inside the area of a settlement
+pub const DEFAULT_SETTLEMENT_FLAT_RATIO: f64 = 0.75;
+/// Number of random centres tried before giving up on the remaining settlements
+pub const DEFAULT_SETTLEMENT_ATTEMPTS: usize = 500;
+
+// volcano default values
+/// Number of Lava flows running down from every volcano of the options generated from a seed
+pub const DEFAULT_VOLCANO_FLOWS: RangeInclusive<usize> = 1..=3;
+/// Minimum distance between two volcanoes
+pub const DEFAULT_VOLCANO_DISTANCE: usize = 16;
+/// Probability for a tile next to a Lava flow to catch fire instead of burning to bare Sand
+pub const DEFAULT_VOLCANO_FIRE_PROBABILITY: f64 = 0.3;
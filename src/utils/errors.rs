@@ -13,4 +13,67 @@ pub enum OxAgError {
     InvalidContentOption(Content),
     InvalidContentOptionProvided,
     MazeSizeTooSmall,
+    /// The settlement density is not between 0 and 1
+    InvalidSettlementDensity,
+    /// Requested and placed Lava lakes, when some of them found no room
+    LavaPlacementFailed(usize, usize),
+    /// The placement rules of the [Content] can never be satisfied
+    UnsatisfiablePlacementRule(Content),
+    /// The value options of the [Content] are out of its bounds
+    InvalidContentValue(Content),
+    /// The capacity options are out of the bounds of the [Content], or it can't be filled
+    InvalidContentCapacity(Content),
+    /// The batch shape parameters of the [Content] are out of bounds
+    InvalidBatchShape(Content),
+    /// The anchor of the [Content] is itself, has an empty distance range or goes around in a loop
+    InvalidAnchor(Content),
+    /// The region can't contain any tile
+    InvalidRegion,
+    /// No free Hill or Mountain tile was found for the source of a river
+    RiverPlacementFailed,
+    /// No free Grass or Hill tile was found for the start of a street
+    StreetPlacementFailed,
+    /// Some of the [Content] could not be placed, see the placement report for the reason
+    ContentPlacementFailed(Content),
+    /// No walkable tile was found for the robot spawn, so one was made walkable
+    SpawnPlacementFailed,
+    /// The robot spawn doesn't follow the spawn strategy, see the spawn report for the tile used
+    SpawnStrategyNotSatisfied,
+    /// The multi-robot spawn options ask for no spawn point
+    InvalidMultiSpawnOptions,
+    /// Requested and found spawn points, when they can't all be placed following the options
+    NotEnoughSpawns(usize, usize),
+    /// The spawn points could be placed, but never with fair resources around them
+    UnfairSpawns,
+    /// The connectivity options are out of bounds, or the path tile type isn't walkable
+    InvalidConnectivityOptions,
+    /// Not enough walkable tiles are reachable from the spawn, even after carving the paths
+    ConnectivityRepairFailed,
+    /// The maze braid factor is not between 0 and 1
+    InvalidMazeBraid,
+    /// The dungeon options are out of bounds, or the world is also set to be a maze
+    InvalidDungeonOptions,
+    /// The maze goal percentile is not between 0 and 1
+    InvalidMazeGoalPercentile,
+    /// The bands of the custom maze difficulty are empty or out of bounds
+    InvalidMazeDifficulty,
+    /// No maze generated from the derived seeds has the requested difficulty, the last one is used
+    MazeDifficultyNotReached,
+    /// The teleport options ask for no pair of teleports
+    InvalidTeleportOptions,
+    /// Requested and placed pairs of teleports, when there is no room for all of them
+    NotEnoughTeleports(usize, usize),
+    /// The spawn strategy depends on the contents, so it can't be combined with the
+    /// connectivity options, the teleports or with regions measured from the spawn
+    IncompatibleSpawnStrategy,
+    /// No free walkable tile for the maze goal can be reached from the spawn
+    MazeGoalPlacementFailed,
+    /// The Lava lakes have no host tiles, no attempts or an empty count or radius range
+    InvalidLavaOptions,
+    /// The settlement radius range is empty, or starts at 0 while settlements are requested
+    InvalidSettlementRadius,
+    /// The longest bridge doesn't fit in the longest street
+    InvalidBridgeLength,
+    /// The volcano count, flow count, flow length or crater radius range is empty
+    InvalidVolcanoOptions,
 }
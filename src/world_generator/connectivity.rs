@@ -0,0 +1,55 @@
+//! Options of the connectivity repair, carving paths so that most of the walkable tiles
+//! can be reached from the spawn, and the report of the connected walkable regions.
+
+use robotics_lib::world::tile::TileType;
+
+use crate::utils::errors::OxAgError;
+use crate::utils::errors::OxAgError::InvalidConnectivityOptions;
+use crate::utils::traits::Validator;
+
+/// Options of the connectivity repair: paths are carved from the region of the spawn
+/// to the unreachable regions gaining the most walkable tiles for every carved one,
+/// bridging water and lava but never the walls of mazes and dungeons
+#[derive(Debug, Clone, PartialEq)]
+pub struct OxAgConnectivityOptions {
+    /// Share of the walkable tiles, between 0 and 1, that must be reachable from the spawn
+    pub min_reachable: f64,
+    /// Walkable [TileType] of the carved tiles, e.g. `Street` or `Sand`
+    pub path_tile_type: TileType,
+    /// Maximum number of carved paths
+    pub max_paths: usize,
+}
+
+impl Default for OxAgConnectivityOptions {
+    fn default() -> Self {
+        Self {
+            min_reachable: 0.9,
+            path_tile_type: TileType::Street,
+            max_paths: 100,
+        }
+    }
+}
+
+impl Validator for OxAgConnectivityOptions {
+    fn validate(&self) -> Result<(), OxAgError> {
+        if !(0.0..=1.0).contains(&self.min_reachable) || !self.path_tile_type.properties().walk() {
+            return Err(InvalidConnectivityOptions);
+        }
+        Ok(())
+    }
+}
+
+/// Connected walkable regions of the last generated world
+#[derive(Debug, Clone)]
+pub struct OxAgConnectivityReport {
+    /// Number of walkable tiles, after the repair
+    pub walkable: usize,
+    /// Sizes of the connected walkable regions before the repair, from the largest
+    pub components: Vec<usize>,
+    /// Number of walkable tiles reachable from the spawn before the repair
+    pub reachable_before: usize,
+    /// Number of walkable tiles reachable from the spawn after the repair
+    pub reachable_after: usize,
+    /// Number of tiles turned into paths
+    pub carved: usize,
+}
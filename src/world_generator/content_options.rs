@@ -1,17 +1,25 @@
 use rand::prelude::StdRng;
 use rand::Rng;
 use rand::SeedableRng;
-use robotics_lib::world::tile::Content;
+use std::ops::RangeInclusive;
+
+use robotics_lib::world::tile::{Content, TileType};
 use strum::IntoEnumIterator;
 
 use crate::utils::constants::*;
 use crate::utils::errors::OxAgError;
-use crate::utils::errors::OxAgError::{InvalidContentOption, InvalidContentOptionProvided};
-use crate::utils::traits::Validator;
+use crate::utils::errors::OxAgError::{
+    InvalidAnchor, InvalidBatchShape, InvalidContentCapacity, InvalidContentOption,
+    InvalidContentOptionProvided, InvalidContentValue, UnsatisfiablePlacementRule,
+};
+use crate::utils::traits::{Container, Validator};
+use crate::world_generator::regions::OxAgRegionOption;
 
 /// Options that determine how the tile [Content] are spawned
+///
+/// Since its placement rules hold lists of [TileType]s, it's not [Copy]: use [Clone] to reuse it.
 /// TODO: Examples & check if doc is ok
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub struct OxAgContentOptions {
     /// Whether this [Content] should be spawned in sets or groups
     pub in_batches: bool,
@@ -27,6 +35,292 @@ pub struct OxAgContentOptions {
     pub max_spawn_number: usize,
     /// The total percentage of this [Content] present in the world
     pub percentage: f64,
+    /// The [TileType]s this [Content] can be placed on, among the ones that can hold it.
+    /// If empty every [TileType] that can hold the [Content] is allowed
+    pub allowed_tile_types: Vec<TileType>,
+    /// At least one of these [TileType]s has to be within `neighbour_radius` of the [Content].
+    /// If empty no neighbour is required
+    pub required_neighbours: Vec<TileType>,
+    /// None of these [TileType]s can be within `neighbour_radius` of the [Content]
+    pub forbidden_neighbours: Vec<TileType>,
+    /// The radius in which the required and forbidden neighbours are checked
+    pub neighbour_radius: usize,
+    /// The heights of the noise map, from -1.0 to 1.0 like the [TileType] levels,
+    /// this [Content] can be placed at, if any
+    pub elevation_band: Option<RangeInclusive<f64>>,
+    /// The minimum distance between two tiles holding this [Content] when not spawned in batches.
+    /// The positions are then sampled as Poisson-disk (blue noise) so they are evenly spread.
+    /// If 0 the positions are independent and uniform
+    pub min_spacing: usize,
+    /// If set, exactly this number of tiles will hold this [Content], counting the ones already
+    /// placed (e.g. by settlements), taking away the extras
+    /// and without overwriting any other [Content].
+    /// It takes the place of every other spawn option
+    pub exact_count: Option<usize>,
+    /// The values (e.g. how many rocks or coins a tile yields) this [Content] can have.
+    /// It can't go over the max of the [Content] properties.
+    /// If not set the values go from 0 to the max of the [Content] properties, excluded
+    pub value_range: Option<RangeInclusive<usize>>,
+    /// How the values of this [Content] are distributed inside the `value_range`
+    pub value_distribution: OxAgValueDistribution,
+    /// How much the values of a batch grow towards its centre, from 0.0 (not at all)
+    /// to 1.0 (the centre holds the max value, the border the min one)
+    pub batch_centre_correlation: f64,
+    /// The capacity options of the [Content] that can be filled
+    /// ([Bin](Content::Bin), [Crate](Content::Crate), [Bank](Content::Bank) and [Market](Content::Market)).
+    /// If set they take the place of the value options
+    pub capacity: Option<OxAgCapacityOptions>,
+    /// The shape of the batches of this [Content]
+    pub batch_shape: OxAgBatchShape,
+    /// What this [Content] does when it lands on a tile already holding another [Content]
+    pub collision_policy: OxAgCollisionPolicy,
+    /// The [Content]s are placed from the lowest to the highest priority,
+    /// the ones with the same priority in the given order.
+    /// Anchors override it: a [Content] always comes after its anchor, whatever their priorities
+    pub priority: usize,
+    /// Another [Content] this one has to be placed near.
+    /// The anchor is always placed first, overriding the `priority`,
+    /// and this [Content] only where it's in range
+    pub anchor: Option<OxAgAnchor>,
+}
+
+/// Anchors the placement of a [Content] to another one, already placed
+#[derive(Debug, Clone, PartialEq)]
+pub struct OxAgAnchor {
+    /// The [Content] to stay near
+    pub content: Content,
+    /// The distances from the nearest anchor [Content], in tiles and diagonals included.
+    /// A distance of 0 means on a tile holding the anchor, that is replacing it
+    pub distance: RangeInclusive<usize>,
+}
+
+/// What a [Content] does when it lands on a tile already holding another [Content]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OxAgCollisionPolicy {
+    /// Only tiles without a [Content] are used
+    NeverOverwrite,
+    /// Only tiles without a [Content] or holding one with a lower priority are used
+    OverwriteLowerPriority,
+    /// Any [Content] already there is replaced
+    #[default]
+    OverwriteAnything,
+}
+
+/// Shape of the batches of a [Content].
+///
+/// The size of each batch goes from half to the whole `max_radius`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OxAgBatchShape {
+    /// A disc where most of the tiles hold the [Content]
+    #[default]
+    Disc,
+    /// An organic blob, from the batch noise field
+    NoiseBlob {
+        /// From 0.0 to 1.0, the higher the smaller and more broken up the blob
+        threshold: f64,
+    },
+    /// Dense in the centre and sparser outwards
+    GaussianFalloff {
+        /// The spread of the [Content] around the centre, relative to the radius
+        sigma: f64,
+    },
+    /// A ring of [Content]
+    Ring {
+        /// How many tiles thick the ring is
+        thickness: usize,
+    },
+    /// An elongated streak, following the contour lines of the terrain
+    Streak {
+        /// How many tiles wide the streak is
+        width: usize,
+    },
+}
+
+/// Capacity options of the [Content] that can be filled
+#[derive(Debug, Clone, PartialEq)]
+pub struct OxAgCapacityOptions {
+    /// The max capacities this [Content] can have, up to the max of the [Content] properties
+    pub capacity_range: RangeInclusive<usize>,
+    /// How much of the capacity is already filled, from 0.0 (empty) to 1.0 (full).
+    /// A [Market](Content::Market) has no fill, so it's ignored
+    pub initial_fill: RangeInclusive<f64>,
+    /// How the capacities are distributed inside the `capacity_range`
+    pub distribution: OxAgValueDistribution,
+}
+
+/// Distribution of the values of a [Content] inside its value range
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OxAgValueDistribution {
+    /// Every value is equally likely
+    #[default]
+    Uniform,
+    /// Values spread around `mean`
+    Normal {
+        /// The most likely value
+        mean: f64,
+        /// How far from the `mean` the values spread
+        std_dev: f64,
+    },
+    /// Low values are the most likely
+    Exponential {
+        /// The average distance of the values from the lowest value of the range
+        mean: f64,
+    },
+    /// Always the same value, which has to be inside the range
+    Fixed(usize),
+}
+
+impl OxAgContentOptions {
+    /// Checks the options against the [Content] they refer to,
+    /// rejecting the placement rules that can never be satisfied and the values out of bounds.
+    pub fn validate_for(&self, content: &Content) -> Result<(), OxAgError> {
+        self.validate()?;
+        let content = content.to_default();
+        if self
+            .allowed_tile_types
+            .iter()
+            .any(|tile_type| !tile_type.properties().can_hold(&content))
+            || (!self.required_neighbours.is_empty()
+                && (self.neighbour_radius == 0
+                    || self
+                        .required_neighbours
+                        .iter()
+                        .all(|tile_type| self.forbidden_neighbours.contains(tile_type))))
+            || self
+                .elevation_band
+                .as_ref()
+                .is_some_and(|band| band.is_empty() || !band.within(&DEFAULT_SPAWN_RANGE_BOUNDS))
+        {
+            Err(UnsatisfiablePlacementRule(content))
+        } else if !self.valid_values(&content) {
+            Err(InvalidContentValue(content))
+        } else if !self.valid_capacity(&content) {
+            Err(InvalidContentCapacity(content))
+        } else if !self.valid_batch_shape() {
+            Err(InvalidBatchShape(content))
+        } else if self.anchor.as_ref().is_some_and(|anchor| {
+            anchor.distance.is_empty() || anchor.content.to_default() == content
+        }) {
+            Err(InvalidAnchor(content))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn valid_values(&self, content: &Content) -> bool {
+        let max = content.properties().max();
+        self.value_range
+            .as_ref()
+            .is_none_or(|range| !range.is_empty() && *range.end() <= max)
+            && self.value_distribution.is_valid(&self.values(max))
+            && (0.0..=1.0).contains(&self.batch_centre_correlation)
+    }
+
+    fn valid_batch_shape(&self) -> bool {
+        match self.batch_shape {
+            OxAgBatchShape::Disc => true,
+            OxAgBatchShape::NoiseBlob { threshold } => (0.0..=1.0).contains(&threshold),
+            OxAgBatchShape::GaussianFalloff { sigma } => sigma > 0.0,
+            OxAgBatchShape::Ring { thickness } => thickness > 0,
+            OxAgBatchShape::Streak { width } => width > 0,
+        }
+    }
+
+    fn valid_capacity(&self, content: &Content) -> bool {
+        let max = content.properties().max();
+        self.capacity.as_ref().is_none_or(|capacity| {
+            matches!(
+                content,
+                Content::Bin(_) | Content::Crate(_) | Content::Bank(_) | Content::Market(_)
+            ) && !capacity.capacity_range.is_empty()
+                && *capacity.capacity_range.end() <= max
+                && !capacity.initial_fill.is_empty()
+                && *capacity.initial_fill.start() >= 0.0
+                && *capacity.initial_fill.end() <= 1.0
+                && capacity.distribution.is_valid(&capacity.capacity_range)
+        })
+    }
+
+    // The values the content can have, from 0 to its max excluded if not set
+    fn values(&self, max: usize) -> RangeInclusive<usize> {
+        self.value_range
+            .clone()
+            .unwrap_or(0..=max.saturating_sub(1))
+    }
+
+    /// Returns the [Content] with a value drawn from the value or capacity options.
+    ///
+    /// `centre_closeness` goes from 0.0 on the border of a batch to 1.0 on its centre,
+    /// it's [None] outside of batches.
+    pub(crate) fn random_value<R: Rng + ?Sized>(
+        &self,
+        content: &Content,
+        rng: &mut R,
+        centre_closeness: Option<f64>,
+    ) -> Content {
+        let max = content.properties().max();
+        if let Some(capacity) = &self.capacity {
+            let size = capacity.distribution.sample(
+                capacity.capacity_range.clone(),
+                rng,
+                centre_closeness.map(|closeness| (closeness, self.batch_centre_correlation)),
+            );
+            let fill =
+                (size as f64 * rng.gen_range(capacity.initial_fill.clone())).round() as usize;
+            return match content {
+                Content::Bin(_) => Content::Bin(fill..size),
+                Content::Crate(_) => Content::Crate(fill..size),
+                Content::Bank(_) => Content::Bank(fill..size),
+                _ => content.to_value(size),
+            };
+        }
+        if max == 0 {
+            return content.to_value(0);
+        }
+        content.to_value(self.value_distribution.sample(
+            self.values(max),
+            rng,
+            centre_closeness.map(|closeness| (closeness, self.batch_centre_correlation)),
+        ))
+    }
+}
+
+impl OxAgValueDistribution {
+    fn is_valid(&self, range: &RangeInclusive<usize>) -> bool {
+        match *self {
+            OxAgValueDistribution::Uniform => true,
+            OxAgValueDistribution::Normal { std_dev, .. } => std_dev >= 0.0,
+            OxAgValueDistribution::Exponential { mean } => mean > 0.0,
+            OxAgValueDistribution::Fixed(value) => range.contains(&value),
+        }
+    }
+
+    // Draws a value in the range, pulled towards the top of the range
+    // by the given (closeness to the batch centre, correlation)
+    fn sample<R: Rng + ?Sized>(
+        &self,
+        range: RangeInclusive<usize>,
+        rng: &mut R,
+        centre: Option<(f64, f64)>,
+    ) -> usize {
+        let (low, high) = (*range.start() as f64, *range.end() as f64);
+        let mut value = match *self {
+            OxAgValueDistribution::Uniform => rng.gen_range(range) as f64,
+            OxAgValueDistribution::Normal { mean, std_dev } => {
+                // Box-Muller transform
+                let (u1, u2) = (1.0 - rng.gen::<f64>(), rng.gen::<f64>());
+                mean + std_dev * (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+            }
+            OxAgValueDistribution::Exponential { mean } => {
+                low - mean * (1.0 - rng.gen::<f64>()).ln()
+            }
+            OxAgValueDistribution::Fixed(value) => value as f64,
+        };
+        if let Some((closeness, correlation)) = centre {
+            value = value * (1.0 - correlation) + (low + (high - low) * closeness) * correlation;
+        }
+        value.round().clamp(low, high) as usize
+    }
 }
 
 impl Validator for OxAgContentOptions {
@@ -48,9 +342,70 @@ impl Validator for Vec<(Content, OxAgContentOptions)> {
         }) {
             Err(InvalidContentOption(out))
         } else {
-            Ok(())
+            self.iter().try_for_each(|(c, op)| op.validate_for(c))?;
+            check_anchor_loops(self, &[])
+        }
+    }
+}
+
+/// Checks that the anchors of the global and the region options don't go around in a loop.
+///
+/// Returns an [InvalidAnchor] error naming a [Content] of the loop.
+pub(crate) fn check_anchor_loops(
+    options: &[(Content, OxAgContentOptions)],
+    region_options: &[OxAgRegionOption],
+) -> Result<(), OxAgError> {
+    match options
+        .iter()
+        .map(|(c, _)| c)
+        .chain(region_options.iter().map(|(_, c, _)| c))
+        .find(|c| anchor_depth(options, region_options, c).is_none())
+    {
+        Some(c) => Err(InvalidAnchor(c.to_default())),
+        None => Ok(()),
+    }
+}
+
+/// Number of anchors to go through, starting from the [Content], before reaching
+/// one without an anchor, along the longest chain of both the global and the region options.
+///
+/// Returns [None] if the anchors go around in a loop.
+pub(crate) fn anchor_depth(
+    options: &[(Content, OxAgContentOptions)],
+    region_options: &[OxAgRegionOption],
+    content: &Content,
+) -> Option<usize> {
+    fn depth(
+        options: &[(Content, OxAgContentOptions)],
+        region_options: &[OxAgRegionOption],
+        chain: &mut Vec<Content>,
+    ) -> Option<usize> {
+        let content = chain.last()?.clone();
+        let anchors = options
+            .iter()
+            .filter(|(c, _)| c.to_default() == content)
+            .map(|(_, option)| option)
+            .chain(
+                region_options
+                    .iter()
+                    .filter(|(_, c, _)| c.to_default() == content)
+                    .map(|(_, _, option)| option),
+            )
+            .filter_map(|option| option.anchor.as_ref())
+            .map(|anchor| anchor.content.to_default())
+            .collect::<Vec<Content>>();
+        let mut longest = 0;
+        for anchor in anchors {
+            if chain.contains(&anchor) {
+                return None;
+            }
+            chain.push(anchor);
+            longest = longest.max(depth(options, region_options, chain)? + 1);
+            chain.pop();
         }
+        Some(longest)
     }
+    depth(options, region_options, &mut vec![content.to_default()])
 }
 
 impl Default for OxAgContentOptions {
@@ -63,6 +418,21 @@ impl Default for OxAgContentOptions {
             with_max_spawn_number: true,
             max_spawn_number: 0,
             percentage: 1.0,
+            allowed_tile_types: vec![],
+            required_neighbours: vec![],
+            forbidden_neighbours: vec![],
+            neighbour_radius: 1,
+            elevation_band: None,
+            min_spacing: 0,
+            exact_count: None,
+            value_range: None,
+            value_distribution: OxAgValueDistribution::Uniform,
+            batch_centre_correlation: 0.0,
+            capacity: None,
+            batch_shape: OxAgBatchShape::Disc,
+            collision_policy: OxAgCollisionPolicy::OverwriteAnything,
+            priority: 0,
+            anchor: None,
         }
     }
 }
@@ -90,6 +460,7 @@ impl OxAgContentOptions {
                                     + DEFAULT_BATCH_DISTANCE),
                             ),
                             percentage: rng.gen_range(0.0..1.0),
+                            ..Default::default()
                         },
                     ))
                 }
@@ -0,0 +1,95 @@
+//! Options of the dungeon worlds, rooms connected by corridors,
+//! and the layout of the generated dungeon.
+
+use std::ops::RangeInclusive;
+
+use crate::utils::errors::OxAgError;
+use crate::utils::errors::OxAgError::InvalidDungeonOptions;
+use crate::utils::traits::Validator;
+
+/// Options of the rooms-and-corridors dungeon mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct OxAgDungeonOptions {
+    /// Number of rooms, fewer are placed if there is no room left for them
+    pub rooms: RangeInclusive<usize>,
+    /// Width and height of a room, in tiles
+    pub room_size: RangeInclusive<usize>,
+    /// Shape of the rooms
+    pub room_shape: OxAgRoomShape,
+    /// Probability that a corridor that is not needed to connect the rooms is dug anyway,
+    /// adding loops between them
+    pub extra_corridors: f64,
+    /// Themes of the rooms with their probability, the first one drawn is used
+    pub themes: Vec<(OxAgRoomTheme, f64)>,
+}
+
+/// Shape of the rooms of a dungeon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OxAgRoomShape {
+    /// The whole rectangle of the room
+    #[default]
+    Rectangular,
+    /// An irregular cave inside the rectangle of the room
+    Cave,
+}
+
+/// Contents that fill a room of a dungeon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OxAgRoomTheme {
+    /// `Coin` and `Crate` on some of the tiles
+    Treasure,
+    /// `Fire` on some of the tiles
+    Traps,
+}
+
+impl Default for OxAgDungeonOptions {
+    fn default() -> Self {
+        Self {
+            rooms: 6..=12,
+            room_size: 4..=10,
+            room_shape: OxAgRoomShape::Rectangular,
+            extra_corridors: 0.1,
+            themes: vec![(OxAgRoomTheme::Treasure, 0.2), (OxAgRoomTheme::Traps, 0.1)],
+        }
+    }
+}
+
+impl Validator for OxAgDungeonOptions {
+    fn validate(&self) -> Result<(), OxAgError> {
+        if self.rooms.is_empty()
+            || self.room_size.is_empty()
+            || *self.room_size.start() == 0
+            || !(0.0..=1.0).contains(&self.extra_corridors)
+            || self
+                .themes
+                .iter()
+                .any(|(_, probability)| !(0.0..=1.0).contains(probability))
+        {
+            return Err(InvalidDungeonOptions);
+        }
+        Ok(())
+    }
+}
+
+/// A room of a generated dungeon
+#[derive(Debug, Clone, PartialEq)]
+pub struct OxAgRoom {
+    /// Top left corner, as `(row, col)`
+    pub from: (usize, usize),
+    /// Bottom right corner, as `(row, col)`, included
+    pub to: (usize, usize),
+    /// The theme of the room, if any
+    pub theme: Option<OxAgRoomTheme>,
+}
+
+/// Layout of the last generated dungeon
+#[derive(Debug, Clone, Default)]
+pub struct OxAgDungeonLayout {
+    /// Every room of the dungeon
+    pub rooms: Vec<OxAgRoom>,
+    /// Corridor tiles right outside a room, as `(row, col)`
+    pub doors: Vec<(usize, usize)>,
+    /// The doors that are the only way between two parts of the dungeon,
+    /// as `(row, col)`
+    pub chokepoints: Vec<(usize, usize)>,
+}
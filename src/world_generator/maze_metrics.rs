@@ -0,0 +1,179 @@
+//! Difficulty metrics of the mazes and dungeons, and the difficulty bands
+//! a maze is regenerated for until it falls in them.
+
+use std::ops::RangeInclusive;
+
+use robotics_lib::world::tile::Tile;
+
+use crate::utils::errors::OxAgError;
+use crate::utils::errors::OxAgError::InvalidMazeDifficulty;
+use crate::utils::traits::Validator;
+use crate::world_generator::maze_options::OxAgMazeSolution;
+use crate::world_generator::spawning_tools::connectivity::steps;
+
+/// Difficulty metrics of a maze, measured on its paths: every walkable tile,
+/// the same ones the goal is reached through
+#[derive(Debug, Clone, PartialEq)]
+pub struct OxAgMazeMetrics {
+    /// Seed the maze was generated with, it differs from the generator one
+    /// when the maze was regenerated to reach a difficulty
+    pub seed: u64,
+    /// Number of tiles with a single way out
+    pub dead_ends: usize,
+    /// Number of tiles with more than two ways out
+    pub decision_points: usize,
+    /// Average number of ways forward at the decision points
+    pub branching_factor: f64,
+    /// Length, in steps, of every corridor between two dead ends or decision points, from the shortest
+    pub corridor_lengths: Vec<usize>,
+    /// Length of the solution, if the goal was placed
+    pub solution_length: Option<usize>,
+    /// Solution length over the straight distance between the spawn and the goal
+    pub tortuosity: Option<f64>,
+}
+
+impl OxAgMazeMetrics {
+    /// Measures the maze, with its solution if any
+    pub fn new(map: &[Vec<Tile>], seed: u64, solution: Option<&OxAgMazeSolution>) -> Self {
+        let size = map.len();
+        let walkable = |(row, col): (usize, usize)| map[row][col].tile_type.properties().walk();
+        let degree = |(row, col): (usize, usize)| {
+            steps(size, row, col)
+                .into_iter()
+                .filter(|&step| walkable(step))
+                .count()
+        };
+        let tiles = (0..size)
+            .flat_map(|row| (0..size).map(move |col| (row, col)))
+            .filter(|&tile| walkable(tile))
+            .collect::<Vec<(usize, usize)>>();
+        let dead_ends = tiles.iter().filter(|&&tile| degree(tile) == 1).count();
+        let junctions = tiles
+            .iter()
+            .map(|&tile| degree(tile))
+            .filter(|&degree| degree > 2)
+            .collect::<Vec<usize>>();
+        let branching_factor = if junctions.is_empty() {
+            0.0
+        } else {
+            junctions.iter().map(|degree| degree - 1).sum::<usize>() as f64 / junctions.len() as f64
+        };
+
+        // Walks every corridor from one of its ends
+        let is_node = |tile: (usize, usize)| degree(tile) != 2;
+        let mut visited = vec![vec![false; size]; size];
+        let mut corridor_lengths = vec![];
+        for &start in tiles.iter().filter(|&&tile| is_node(tile)) {
+            for next in steps(size, start.0, start.1) {
+                if !walkable(next) || visited[next.0][next.1] {
+                    continue;
+                }
+                if is_node(next) {
+                    if start < next {
+                        corridor_lengths.push(1);
+                    }
+                    continue;
+                }
+                let (mut previous, mut current, mut length) = (start, next, 1);
+                while !is_node(current) {
+                    visited[current.0][current.1] = true;
+                    let Some(step) = steps(size, current.0, current.1)
+                        .into_iter()
+                        .find(|&step| walkable(step) && step != previous)
+                    else {
+                        break;
+                    };
+                    (previous, current) = (current, step);
+                    length += 1;
+                }
+                corridor_lengths.push(length);
+            }
+        }
+        corridor_lengths.sort_unstable();
+
+        let tortuosity = solution.and_then(|solution| {
+            let (start, goal) = (solution.path.first()?, solution.goal);
+            let straight = start.0.abs_diff(goal.0) + start.1.abs_diff(goal.1);
+            (straight > 0).then(|| solution.length as f64 / straight as f64)
+        });
+        Self {
+            seed,
+            dead_ends,
+            decision_points: junctions.len(),
+            branching_factor,
+            corridor_lengths,
+            solution_length: solution.map(|solution| solution.length),
+            tortuosity,
+        }
+    }
+
+    /// Average length of the corridors
+    pub fn mean_corridor_length(&self) -> f64 {
+        if self.corridor_lengths.is_empty() {
+            return 0.0;
+        }
+        self.corridor_lengths.iter().sum::<usize>() as f64 / self.corridor_lengths.len() as f64
+    }
+}
+
+/// Difficulty a maze is regenerated for, with derived seeds, until its metrics fall in the band
+#[derive(Debug, Clone, PartialEq)]
+pub enum OxAgMazeDifficulty {
+    /// Straight solutions and few dead ends:
+    /// tortuosity from 1 to 2, at most 10% of dead ends
+    Easy,
+    /// Solutions that wind around the maze, with some dead ends:
+    /// tortuosity from 2 to 4, from 5% to 25% of dead ends
+    Medium,
+    /// Twisted solutions, with dead ends to get lost in:
+    /// tortuosity from 4 with no upper bound, at least 5% of dead ends
+    Hard,
+    /// Tortuosity and share of dead ends among the path tiles of the maze
+    Custom {
+        /// Band of the solution length over the straight distance to the goal, from 1.
+        /// Use [f64::INFINITY] as the end to leave it open
+        tortuosity: RangeInclusive<f64>,
+        /// Band of the share of the path tiles that are dead ends, between 0 and 1
+        dead_end_share: RangeInclusive<f64>,
+    },
+}
+
+impl OxAgMazeDifficulty {
+    /// The tortuosity and dead end share bands of the difficulty
+    fn band(&self) -> (RangeInclusive<f64>, RangeInclusive<f64>) {
+        match self {
+            OxAgMazeDifficulty::Easy => (1.0..=2.0, 0.0..=0.1),
+            OxAgMazeDifficulty::Medium => (2.0..=4.0, 0.05..=0.25),
+            // Open-ended: no solution is too twisted
+            OxAgMazeDifficulty::Hard => (4.0..=f64::INFINITY, 0.05..=1.0),
+            OxAgMazeDifficulty::Custom {
+                tortuosity,
+                dead_end_share,
+            } => (tortuosity.clone(), dead_end_share.clone()),
+        }
+    }
+
+    /// Whether the metrics of a maze with `paths` path tiles fall in the band
+    pub(crate) fn contains(&self, metrics: &OxAgMazeMetrics, paths: usize) -> bool {
+        let (tortuosity, dead_end_share) = self.band();
+        metrics
+            .tortuosity
+            .is_some_and(|value| tortuosity.contains(&value))
+            && dead_end_share.contains(&(metrics.dead_ends as f64 / paths.max(1) as f64))
+    }
+}
+
+impl Validator for OxAgMazeDifficulty {
+    fn validate(&self) -> Result<(), OxAgError> {
+        let (tortuosity, dead_end_share) = self.band();
+        if tortuosity.is_empty()
+            || dead_end_share.is_empty()
+            || *tortuosity.start() < 1.0
+            || *dead_end_share.start() < 0.0
+            || *dead_end_share.end() > 1.0
+        {
+            return Err(InvalidMazeDifficulty);
+        }
+        Ok(())
+    }
+}
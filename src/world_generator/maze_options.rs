@@ -0,0 +1,31 @@
+//! Options of the maze worlds, like the algorithm carving the corridors,
+//! and the solution of the generated maze.
+
+/// Algorithm used to carve the maze when the world is built as a maze
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OxAgMazeAlgorithm {
+    /// Depth-first with backtracking: long winding corridors with few branches
+    #[default]
+    RecursiveBacktracker,
+    /// Randomized Prim: many short dead ends branching from the start
+    Prim,
+    /// Randomized Kruskal: many short dead ends spread evenly
+    Kruskal,
+    /// Wilson, with loop-erased random walks: every maze is equally likely
+    Wilson,
+    /// Eller, row by row: horizontal corridors with a bias along the rows
+    Eller,
+    /// Recursive division: long straight walls splitting the map in chambers
+    RecursiveDivision,
+}
+
+/// Goal of the last generated maze, with the shortest path to it from the spawn
+#[derive(Debug, Clone, PartialEq)]
+pub struct OxAgMazeSolution {
+    /// The tile holding the goal `JollyBlock(1)`, as `(row, col)`
+    pub goal: (usize, usize),
+    /// Every tile of the shortest path from the spawn to the goal, both included
+    pub path: Vec<(usize, usize)>,
+    /// Number of steps of the shortest path
+    pub length: usize,
+}
@@ -1,20 +1,48 @@
 use std::collections::HashMap;
 
+use rand::prelude::StdRng;
+use rand::{Rng, SeedableRng};
 use robotics_lib::world::environmental_conditions::EnvironmentalConditions;
 use robotics_lib::world::tile::{Content, Tile};
 use robotics_lib::world::world_generator::Generator;
 
+use crate::utils::constants::DEFAULT_MAZE_DIFFICULTY_ATTEMPTS;
+use crate::utils::errors::OxAgError;
+use crate::utils::errors::OxAgError::MazeDifficultyNotReached;
+use crate::utils::traits::Validator;
+use crate::world_generator::connectivity::{OxAgConnectivityOptions, OxAgConnectivityReport};
 use crate::world_generator::content_options::OxAgContentOptions;
+use crate::world_generator::dungeon::{OxAgDungeonLayout, OxAgDungeonOptions};
+use crate::world_generator::maze_metrics::{OxAgMazeDifficulty, OxAgMazeMetrics};
+use crate::world_generator::maze_options::{OxAgMazeAlgorithm, OxAgMazeSolution};
+use crate::world_generator::multi_spawn::{OxAgMultiSpawnOptions, OxAgMultiSpawnWorld};
+use crate::world_generator::placement_report::OxAgPlacementReport;
+use crate::world_generator::regions::OxAgRegionOption;
+use crate::world_generator::spawn_strategy::{OxAgSpawnReport, OxAgSpawnStrategy};
 use crate::world_generator::spawning_tools::maze::maze_builder_init;
-use crate::world_generator::spawning_tools::{matrix_spawn::f64_mat, F64MatData, MazeBuilder};
+use crate::world_generator::spawning_tools::multi_spawn::choose_spawns;
+use crate::world_generator::spawning_tools::{
+    matrix_spawn::f64_mat, F64MatData, MazeBuilder, TileMat,
+};
+use crate::world_generator::teleports::{OxAgTeleportOptions, OxAgTeleportReport};
 use crate::world_generator::tile_type_options::OxAgTileTypeOptions;
 use crate::world_generator::world_generator_builder::OxAgWorldGeneratorBuilder;
 
+pub mod connectivity;
 pub mod content_options;
+pub mod dungeon;
 pub mod environmental_condition_options;
+pub mod maze_metrics;
+pub mod maze_options;
+pub mod multi_spawn;
+pub mod placement_report;
 pub mod presets;
+pub mod regions;
 mod serial;
-mod spawning_tools;
+pub mod shapes;
+pub mod spawn_strategy;
+pub(crate) mod spawning_tools;
+pub mod teleports;
 pub mod tile_type_options;
 pub mod world_generator_builder;
 
@@ -52,6 +80,15 @@ pub struct OxAgWorldGenerator {
     /// [HashMap] with the [Content] as the key and [OxAgContentOptions] as its value.
     pub(crate) content_options: Vec<(Content, OxAgContentOptions)>,
 
+    /// [OxAgContentOptions] that take the place of the global ones inside a region.
+    pub(crate) region_options: Vec<OxAgRegionOption>,
+
+    /// [OxAgSpawnStrategy] used to choose the robot spawn.
+    pub(crate) spawn_strategy: OxAgSpawnStrategy,
+
+    /// Optional [OxAgConnectivityOptions] used to repair the map, no repair if [None]
+    pub(crate) connectivity_options: Option<OxAgConnectivityOptions>,
+
     /// [EnvironmentalConditions] that will be used in the generated world
     pub(crate) environmental_conditions: EnvironmentalConditions,
 
@@ -66,17 +103,63 @@ pub struct OxAgWorldGenerator {
 
     pub(crate) maze: bool,
 
+    /// [OxAgMazeAlgorithm] used to carve the maze
+    pub(crate) maze_algorithm: OxAgMazeAlgorithm,
+
+    /// Share of the dead ends of the maze that are opened, between 0 and 1
+    pub(crate) maze_braid: f64,
+
+    /// Number of extra loops added to the maze
+    pub(crate) maze_loops: usize,
+
+    /// Percentile of the distance from the spawn where the goal of the maze is placed, between 0 and 1
+    pub(crate) maze_goal_percentile: f64,
+
+    /// [OxAgMazeSolution] of the last generated maze
+    pub(crate) maze_solution: Option<OxAgMazeSolution>,
+
+    /// Optional [OxAgMazeDifficulty] the maze is regenerated for
+    pub(crate) maze_difficulty: Option<OxAgMazeDifficulty>,
+
+    /// [OxAgMazeMetrics] of the last generated maze
+    pub(crate) maze_metrics: Option<OxAgMazeMetrics>,
+
+    /// Optional [OxAgDungeonOptions], the world is a dungeon instead of a maze if set
+    pub(crate) dungeon_options: Option<OxAgDungeonOptions>,
+
+    /// [OxAgDungeonLayout] of the last generated dungeon
+    pub(crate) dungeon_layout: Option<OxAgDungeonLayout>,
+
+    /// Optional [OxAgTeleportOptions], no teleport is placed if [None]
+    pub(crate) teleport_options: Option<OxAgTeleportOptions>,
+
+    /// [OxAgTeleportReport] of the last generated world
+    pub(crate) teleport_report: Option<OxAgTeleportReport>,
+
     pub(crate) score_map: Option<HashMap<Content, f32>>,
 
-    pub(crate) map_save: Option<(
-        Vec<Vec<Tile>>,
-        (usize, usize),
-        EnvironmentalConditions,
-        f32,
-        Option<HashMap<Content, f32>>,
-    )>,
+    /// [OxAgPlacementReport] of the last generated world
+    pub(crate) placement_report: OxAgPlacementReport,
+
+    /// [OxAgSpawnReport] of the last generated world
+    pub(crate) spawn_report: Option<OxAgSpawnReport>,
+
+    /// [OxAgConnectivityReport] of the last generated world
+    pub(crate) connectivity_report: Option<OxAgConnectivityReport>,
+
+    pub(crate) map_save: Option<OxAgWorld>,
 }
 
+/// World returned by the [Generator]: the map, the spawn point, the [EnvironmentalConditions],
+/// the score and the score map.
+pub type OxAgWorld = (
+    Vec<Vec<Tile>>,
+    (usize, usize),
+    EnvironmentalConditions,
+    f32,
+    Option<HashMap<Content, f32>>,
+);
+
 impl OxAgWorldGenerator {
     /// Returns a new builder.
     ///
@@ -100,6 +183,69 @@ impl OxAgWorldGenerator {
         self.size
     }
 
+    /// Returns the [OxAgMazeAlgorithm] used to carve the maze
+    pub fn get_maze_algorithm(&self) -> OxAgMazeAlgorithm {
+        self.maze_algorithm
+    }
+
+    /// Returns the share of the dead ends of the maze that are opened
+    pub fn get_maze_braid(&self) -> f64 {
+        self.maze_braid
+    }
+
+    /// Returns the number of extra loops added to the maze
+    pub fn get_maze_loops(&self) -> usize {
+        self.maze_loops
+    }
+
+    /// Returns the percentile of the distance from the spawn where the goal of the maze is placed
+    pub fn get_maze_goal_percentile(&self) -> f64 {
+        self.maze_goal_percentile
+    }
+
+    /// Returns the goal of the last generated maze or dungeon with the shortest path to it,
+    /// to grade robots by how close they get to it.
+    ///
+    /// [None] if the world is not a maze, no world was generated yet or the goal couldn't be placed.
+    pub fn get_maze_solution(&self) -> Option<&OxAgMazeSolution> {
+        self.maze_solution.as_ref()
+    }
+
+    /// Returns the [OxAgMazeDifficulty] the maze is regenerated for, if any
+    pub fn get_maze_difficulty(&self) -> Option<&OxAgMazeDifficulty> {
+        self.maze_difficulty.as_ref()
+    }
+
+    /// Returns the dead ends, decision points, corridors and tortuosity of the last generated
+    /// maze or dungeon, with the seed it was generated from.
+    ///
+    /// [None] if the world is not a maze or no world was generated yet.
+    pub fn get_maze_metrics(&self) -> Option<&OxAgMazeMetrics> {
+        self.maze_metrics.as_ref()
+    }
+
+    /// Returns the [OxAgDungeonOptions] if the world is a dungeon
+    pub fn get_dungeon_options(&self) -> Option<&OxAgDungeonOptions> {
+        self.dungeon_options.as_ref()
+    }
+
+    /// Returns the rooms, doors and chokepoints of the last generated dungeon,
+    /// [None] if the world is not a dungeon or no world was generated yet.
+    pub fn get_dungeon_layout(&self) -> Option<&OxAgDungeonLayout> {
+        self.dungeon_layout.as_ref()
+    }
+
+    /// Returns the [OxAgTeleportOptions] if the world has teleports
+    pub fn get_teleport_options(&self) -> Option<&OxAgTeleportOptions> {
+        self.teleport_options.as_ref()
+    }
+
+    /// Returns the pairs of teleports of the last generated world and the regions they link,
+    /// [None] if the world has no teleports or no world was generated yet.
+    pub fn get_teleport_report(&self) -> Option<&OxAgTeleportReport> {
+        self.teleport_report.as_ref()
+    }
+
     /// Return the score of the world
     pub fn get_score(&self) -> f32 {
         self.score
@@ -130,6 +276,11 @@ impl OxAgWorldGenerator {
         &self.content_options
     }
 
+    /// Returns the [OxAgContentOptions] that take the place of the global ones inside a region
+    pub fn get_region_options(&self) -> &Vec<OxAgRegionOption> {
+        &self.region_options
+    }
+
     /// Returns the [EnvironmentalConditions] that will be used in the generated world
     pub fn get_environmental_conditions(&self) -> &EnvironmentalConditions {
         &self.environmental_conditions
@@ -140,6 +291,34 @@ impl OxAgWorldGenerator {
         &self.score_map
     }
 
+    /// Returns how many tiles of every spawned [Content] were requested and placed
+    /// in the last generated world, with the reasons of the failures.
+    pub fn get_placement_report(&self) -> &OxAgPlacementReport {
+        &self.placement_report
+    }
+
+    /// Returns the [OxAgSpawnStrategy] used to choose the robot spawn
+    pub fn get_spawn_strategy(&self) -> &OxAgSpawnStrategy {
+        &self.spawn_strategy
+    }
+
+    /// Returns how the spawn of the last generated world was chosen,
+    /// [None] if no world was generated yet.
+    pub fn get_spawn_report(&self) -> Option<&OxAgSpawnReport> {
+        self.spawn_report.as_ref()
+    }
+
+    /// Returns the [OxAgConnectivityOptions] used to repair the map, if any
+    pub fn get_connectivity_options(&self) -> Option<&OxAgConnectivityOptions> {
+        self.connectivity_options.as_ref()
+    }
+
+    /// Returns the connected walkable regions of the last generated world and how it was repaired,
+    /// [None] if no world was generated yet.
+    pub fn get_connectivity_report(&self) -> Option<&OxAgConnectivityReport> {
+        self.connectivity_report.as_ref()
+    }
+
     /// Returns matrix of floats generated from the seed.
     ///
     /// This float values are meant to be mapped to tile types considering the tile type spawn levels.
@@ -147,53 +326,188 @@ impl OxAgWorldGenerator {
         f64_mat(self.seed, self.size, self.with_info)
     }
 
-    /// Returns a matrix filled with wall.
+    /// Returns a matrix filled with wall, generated from the given seed.
+    ///
+    /// This matrix will become a maze or a dungeon.
+    fn generate_base_maze(&self, seed: u64) -> MazeBuilder {
+        maze_builder_init(
+            seed,
+            self.size,
+            self.with_info,
+            self.height_multiplier,
+            self.maze_algorithm,
+            self.maze_braid,
+            self.maze_loops,
+            self.maze_goal_percentile,
+        )
+    }
+
+    /// Builds the maze or the dungeon and measures it.
     ///
-    /// This matrix will become a maze.
-    fn generate_base_maze(&mut self) -> MazeBuilder {
-        if self.size % 2 == 0 {
+    /// With a difficulty it's regenerated from seeds derived from the generator one
+    /// until its metrics fall in the band, the last one is kept if none does.
+    fn generate_maze(&mut self) -> (TileMat, (usize, usize)) {
+        if self.dungeon_options.is_none() && self.size % 2 == 0 {
             self.size += 1;
         }
-        maze_builder_init(self.seed, self.size)
+        let mut seeds = StdRng::seed_from_u64(self.seed);
+        let mut last = None;
+        for attempt in 1..=DEFAULT_MAZE_DIFFICULTY_ATTEMPTS {
+            let seed = if attempt == 1 { self.seed } else { seeds.gen() };
+            let (map, spawn, metrics) = self.measure_maze(seed);
+            let paths = map
+                .map
+                .iter()
+                .flatten()
+                .filter(|tile| tile.tile_type.properties().walk())
+                .count();
+            if self
+                .maze_difficulty
+                .as_ref()
+                .is_none_or(|difficulty| difficulty.contains(&metrics, paths))
+            {
+                self.maze_metrics = Some(metrics);
+                return (map, spawn);
+            }
+            last = Some((map, spawn, metrics));
+        }
+
+        if self.with_info {
+            println!("No maze of the requested difficulty was found");
+        }
+        let (mut map, spawn, metrics) = last.unwrap_or_else(|| self.measure_maze(self.seed));
+        map.errors.push(MazeDifficultyNotReached);
+        self.maze_metrics = Some(metrics);
+        (map, spawn)
+    }
+
+    /// Builds the maze or the dungeon from the given seed and measures it
+    fn measure_maze(&self, seed: u64) -> (TileMat, (usize, usize), OxAgMazeMetrics) {
+        let (map, spawn) = self.generate_base_maze(seed).builder(
+            self.get_content_options(),
+            self.get_region_options(),
+            self.get_spawn_strategy(),
+            self.connectivity_options.as_ref(),
+            self.teleport_options.as_ref(),
+            self.dungeon_options.as_ref(),
+            self.get_tile_type_options(),
+        );
+        let metrics = OxAgMazeMetrics::new(&map.map, seed, map.maze_solution.as_ref());
+        (map, spawn, metrics)
     }
 }
 
-impl Generator for OxAgWorldGenerator {
-    fn gen(
+impl OxAgWorldGenerator {
+    /// Generates the world like [gen](Generator::gen), but fails instead of giving back
+    /// a world where something could not be placed.
+    ///
+    /// Returns the world or the first [OxAgError] met, naming the stage or the [Content]
+    /// that couldn't be placed.
+    ///
+    /// # Example
+    /// ```rust
+    /// use oxagworldgenerator::world_generator::world_generator_builder::OxAgWorldGeneratorBuilder;
+    ///
+    /// let mut generator = OxAgWorldGeneratorBuilder::new()
+    ///     .set_size(100)
+    ///     .set_with_info(false)
+    ///     .build()
+    ///     .unwrap();
+    /// match generator.try_generate() {
+    ///     Ok((map, spawn, ..)) => println!("{}x{} world, spawn in {:?}", map.len(), map.len(), spawn),
+    ///     Err(error) => println!("{:?}", error),
+    /// }
+    /// ```
+    pub fn try_generate(&mut self) -> Result<OxAgWorld, OxAgError> {
+        let (world, errors) = self.generate();
+        match errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(world),
+        }
+    }
+
+    /// Generates the world like [gen](Generator::gen), with several spawn points
+    /// for multi-robot scenarios.
+    ///
+    /// The first spawn point is the one [gen](Generator::gen) would return,
+    /// the others follow the [OxAgMultiSpawnOptions].
+    ///
+    /// Returns the world or an [OxAgError] if the spawn points can't follow the options.
+    ///
+    /// # Example
+    /// ```rust
+    /// use oxagworldgenerator::world_generator::multi_spawn::{OxAgMultiSpawnOptions, OxAgSpawnFairness};
+    /// use oxagworldgenerator::world_generator::world_generator_builder::OxAgWorldGeneratorBuilder;
+    ///
+    /// let mut generator = OxAgWorldGeneratorBuilder::new()
+    ///     .set_size(100)
+    ///     .set_with_info(false)
+    ///     .build()
+    ///     .unwrap();
+    /// let world = generator.generate_with_spawns(&OxAgMultiSpawnOptions {
+    ///     count: 4,
+    ///     min_distance: 20,
+    ///     same_region: true,
+    ///     fairness: Some(OxAgSpawnFairness {
+    ///         radius: 10,
+    ///         resources: vec![],
+    ///         tolerance: 20,
+    ///     }),
+    /// });
+    /// if let Ok((_, spawns, ..)) = world {
+    ///     println!("Spawns: {:?}", spawns);
+    /// }
+    /// ```
+    pub fn generate_with_spawns(
         &mut self,
-    ) -> (
-        Vec<Vec<Tile>>,
-        (usize, usize),
-        EnvironmentalConditions,
-        f32,
-        Option<HashMap<Content, f32>>,
-    ) {
+        options: &OxAgMultiSpawnOptions,
+    ) -> Result<OxAgMultiSpawnWorld, OxAgError> {
+        options.validate()?;
+        let ((map, spawn, environmental_conditions, score, score_map), _) = self.generate();
+        let spawns = choose_spawns(&map, spawn, options, self.seed)?;
+        Ok((map, spawns, environmental_conditions, score, score_map))
+    }
+
+    /// Generates the world, collecting every placement that failed along the way
+    fn generate(&mut self) -> (OxAgWorld, Vec<OxAgError>) {
         if self.map_save.is_some() {
-            return self.map_save.clone().unwrap();
+            return (self.map_save.clone().unwrap(), vec![]);
         }
-        if self.maze {
-            let (map, spawn) = self
-                .generate_base_maze()
-                .builder(self.get_content_options(), self.get_tile_type_options());
-            (
-                map,
-                spawn,
-                self.environmental_conditions.clone(),
-                self.score,
-                self.score_map.clone(),
-            )
+        self.maze_metrics = None;
+        let (map, spawn) = if self.maze || self.dungeon_options.is_some() {
+            self.generate_maze()
         } else {
-            let (map, spawn) = self
-                .generate_float_matrix()
+            self.generate_float_matrix()
                 .to_tile_mat(self.get_tile_type_options(), self.height_multiplier)
-                .spawn_contents(self.get_content_options());
+                .spawn_contents(
+                    self.get_content_options(),
+                    self.get_region_options(),
+                    self.get_spawn_strategy(),
+                    self.connectivity_options.as_ref(),
+                    self.teleport_options.as_ref(),
+                )
+        };
+        self.teleport_report = map.teleport_report;
+        self.placement_report = map.placement_report;
+        self.spawn_report = map.spawn_report;
+        self.connectivity_report = map.connectivity_report;
+        self.dungeon_layout = map.dungeon_layout;
+        self.maze_solution = map.maze_solution;
+        (
             (
                 map.map,
                 spawn,
                 self.environmental_conditions.clone(),
                 self.score,
                 self.score_map.clone(),
-            )
-        }
+            ),
+            map.errors,
+        )
+    }
+}
+
+impl Generator for OxAgWorldGenerator {
+    fn gen(&mut self) -> OxAgWorld {
+        self.generate().0
     }
 }
@@ -0,0 +1,65 @@
+//! Options of the multi-robot worlds, with several spawn points far apart and fair resources.
+
+use std::collections::HashMap;
+
+use robotics_lib::world::environmental_conditions::EnvironmentalConditions;
+use robotics_lib::world::tile::{Content, Tile};
+
+use crate::utils::errors::OxAgError;
+use crate::utils::errors::OxAgError::InvalidMultiSpawnOptions;
+use crate::utils::traits::Validator;
+
+/// Constraints of the spawn points of a multi-robot world
+#[derive(Debug, Clone, PartialEq)]
+pub struct OxAgMultiSpawnOptions {
+    /// Number of spawn points, at least 1
+    pub count: usize,
+    /// Minimum distance (in tiles) between any two spawn points
+    pub min_distance: usize,
+    /// Whether every spawn point must be in the same connected walkable region,
+    /// so that every robot can reach the others
+    pub same_region: bool,
+    /// Optional fairness of the resources around the spawn points
+    pub fairness: Option<OxAgSpawnFairness>,
+}
+
+/// Every spawn point must have about the same resources around it
+#[derive(Debug, Clone, PartialEq)]
+pub struct OxAgSpawnFairness {
+    /// Radius (in tiles) around every spawn point where the resources are counted
+    pub radius: usize,
+    /// [Content]s that are counted as resources, every [Content] if empty
+    pub resources: Vec<Content>,
+    /// Maximum difference between the most and the least resources around a spawn point
+    pub tolerance: usize,
+}
+
+impl Default for OxAgMultiSpawnOptions {
+    fn default() -> Self {
+        Self {
+            count: 2,
+            min_distance: 0,
+            same_region: true,
+            fairness: None,
+        }
+    }
+}
+
+impl Validator for OxAgMultiSpawnOptions {
+    fn validate(&self) -> Result<(), OxAgError> {
+        if self.count == 0 {
+            return Err(InvalidMultiSpawnOptions);
+        }
+        Ok(())
+    }
+}
+
+/// World returned by [generate_with_spawns](crate::world_generator::OxAgWorldGenerator::generate_with_spawns):
+/// like the [OxAgWorld](crate::world_generator::OxAgWorld), with every spawn point.
+pub type OxAgMultiSpawnWorld = (
+    Vec<Vec<Tile>>,
+    Vec<(usize, usize)>,
+    EnvironmentalConditions,
+    f32,
+    Option<HashMap<Content, f32>>,
+);
@@ -0,0 +1,64 @@
+//! Report of the [Content] placed in the last generated world, telling for every [Content]
+//! how many tiles were requested, how many hold it in the end and why the others don't.
+
+use robotics_lib::world::tile::Content;
+
+/// Why some of the requested [Content] could not be placed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OxAgPlacementFailure {
+    /// No tile satisfies the placement rules of the [Content]
+    NoValidTile,
+    /// Every valid tile already holds another [Content]
+    NoFreeTile,
+    /// Not enough valid tiles are far enough from each other for the `min_spacing`
+    SpacingTooLarge,
+    /// Some of the placed [Content] were overwritten by a later [Content]
+    Overwritten,
+    /// The anchor [Content] is nowhere in the map
+    AnchorNotFound,
+    /// Some tiles covered by the batches break the placement rules or hold another [Content],
+    /// so the batches are cut there
+    Clipped,
+}
+
+/// How many tiles of a [Content] were requested and actually placed in the generated world
+#[derive(Debug, Clone)]
+pub struct OxAgContentPlacement {
+    /// The [Content] this entry refers to
+    pub content: Content,
+    /// The number of tiles that were supposed to hold the [Content]
+    pub requested: usize,
+    /// The number of tiles holding the [Content] in the final world
+    pub placed: usize,
+    /// The number of requested tiles that don't hold the [Content]
+    pub failed: usize,
+    /// The reasons of the failures, empty if everything was placed
+    pub reasons: Vec<OxAgPlacementFailure>,
+}
+
+impl OxAgContentPlacement {
+    pub(crate) fn new(content: &Content) -> Self {
+        Self {
+            content: content.to_default(),
+            requested: 0,
+            placed: 0,
+            failed: 0,
+            reasons: vec![],
+        }
+    }
+
+    /// Adds up the entry of another pass of the same [Content], like the one of a region
+    pub(crate) fn merge(&mut self, other: Self) {
+        self.requested += other.requested;
+        self.placed += other.placed;
+        self.failed += other.failed;
+        for reason in other.reasons {
+            if !self.reasons.contains(&reason) {
+                self.reasons.push(reason);
+            }
+        }
+    }
+}
+
+/// Placement report of every spawned [Content], one entry each in spawn order
+pub type OxAgPlacementReport = Vec<OxAgContentPlacement>;
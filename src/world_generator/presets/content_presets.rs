@@ -18,6 +18,24 @@ use crate::world_generator::content_options::OxAgContentOptions;
 /// │ with_max_spawn_number│ If true consider the max_spawn_number      │
 /// │ max_spawn_number     │ Maximum number of content that can spawn   │
 /// │ percentage           │ Percentage of the content that will spawn  │
+/// │ allowed_tile_types   │ Tile types it can spawn on, empty for all  │
+/// │ required_neighbours  │ Tile types that have to be nearby          │
+/// │ forbidden_neighbours │ Tile types that can't be nearby            │
+/// │ neighbour_radius     │ Radius in which the neighbours are checked │
+/// │ elevation_band       │ Noise heights it can spawn at, if any      │
+/// │ min_spacing          │ Minimum distance between two of them       │
+/// │ exact_count          │ If set, exact number of them in the world  │
+/// │ value_range          │ Values it can have, up to its max          │
+/// │ value_distribution   │ Distribution of the values in the range    │
+/// │ batch_centre_        │ How much the values grow towards the       │
+/// │   correlation        │ centre of the batches, from 0.0 to 1.0     │
+/// │ capacity             │ Capacity and initial fill of the contents  │
+/// │                      │ that can be filled, e.g. Bin               │
+/// │ batch_shape          │ Shape of the batches                       │
+/// │ collision_policy     │ Whether it can replace other contents      │
+/// │ priority             │ Contents are placed from the lowest to the │
+/// │                      │ highest priority                           │
+/// │ anchor               │ Content it has to be near, if any          │
 /// └──────────────────────┴────────────────────────────────────────────┘
 /// </pre>
 ///
@@ -79,6 +97,7 @@ const DEFAULT: fn() -> Vec<(Content, OxAgContentOptions)> = || {
                 with_max_spawn_number: false,
                 max_spawn_number: 0,
                 percentage: 0.08,
+                ..Default::default()
             },
         ),
         (
@@ -91,6 +110,7 @@ const DEFAULT: fn() -> Vec<(Content, OxAgContentOptions)> = || {
                 with_max_spawn_number: false,
                 max_spawn_number: 100,
                 percentage: 0.3,
+                ..Default::default()
             },
         ),
         (
@@ -103,6 +123,7 @@ const DEFAULT: fn() -> Vec<(Content, OxAgContentOptions)> = || {
                 with_max_spawn_number: false,
                 max_spawn_number: 0,
                 percentage: 0.03,
+                ..Default::default()
             },
         ),
         (
@@ -115,6 +136,7 @@ const DEFAULT: fn() -> Vec<(Content, OxAgContentOptions)> = || {
                 with_max_spawn_number: true,
                 max_spawn_number: 6,
                 percentage: 0.04,
+                ..Default::default()
             },
         ),
         (
@@ -127,6 +149,7 @@ const DEFAULT: fn() -> Vec<(Content, OxAgContentOptions)> = || {
                 with_max_spawn_number: false,
                 max_spawn_number: 0,
                 percentage: 0.07,
+                ..Default::default()
             },
         ),
         (
@@ -139,6 +162,7 @@ const DEFAULT: fn() -> Vec<(Content, OxAgContentOptions)> = || {
                 with_max_spawn_number: false,
                 max_spawn_number: 0,
                 percentage: 0.01,
+                ..Default::default()
             },
         ),
         (
@@ -151,6 +175,7 @@ const DEFAULT: fn() -> Vec<(Content, OxAgContentOptions)> = || {
                 with_max_spawn_number: false,
                 max_spawn_number: 0,
                 percentage: 0.01,
+                ..Default::default()
             },
         ),
         (
@@ -163,6 +188,7 @@ const DEFAULT: fn() -> Vec<(Content, OxAgContentOptions)> = || {
                 with_max_spawn_number: false,
                 max_spawn_number: 0,
                 percentage: 0.01,
+                ..Default::default()
             },
         ),
         (
@@ -175,6 +201,7 @@ const DEFAULT: fn() -> Vec<(Content, OxAgContentOptions)> = || {
                 with_max_spawn_number: false,
                 max_spawn_number: 0,
                 percentage: 1.0,
+                ..Default::default()
             },
         ),
         (
@@ -187,6 +214,7 @@ const DEFAULT: fn() -> Vec<(Content, OxAgContentOptions)> = || {
                 with_max_spawn_number: false,
                 max_spawn_number: 0,
                 percentage: 0.2,
+                ..Default::default()
             },
         ),
     ])
@@ -1,5 +1,7 @@
+use crate::utils::constants::DEFAULT_LAVA_ATTEMPTS;
 use crate::utils::traits::Loadable;
 use crate::world_generator::tile_type_options::OxAgTileTypeOptions;
+use robotics_lib::world::tile::TileType::{Grass, Hill};
 
 /// # Presets
 /// List of available presets
@@ -20,6 +22,18 @@ use crate::world_generator::tile_type_options::OxAgTileTypeOptions;
 /// │ street_len           │ Length of street      │
 /// │ lava_n               │ Number of lava lake   │
 /// │ lava_radius          │ Radius of lava lake   │
+/// │ lava_host_tiles      │ Tiles hosting lava    │
+/// │ lava_min_distance    │ Lava lakes distance   │
+/// │ lava_spawn_distance  │ Lava spawn distance   │
+/// │ lava_attempts        │ Lava attempts budget  │
+/// │ settlement_n         │ Number of settlements │
+/// │ settlement_radius    │ Radius of settlement  │
+/// │ settlement_density   │ Building probability  │
+/// │ bridge_max_len       │ Max length of bridge  │
+/// │ volcano_n            │ Number of volcanoes   │
+/// │ volcano_flows        │ Lava flows of volcano │
+/// │ volcano_flow_len     │ Length of lava flows  │
+/// │ volcano_crater_radius│ Radius of crater      │
 /// └──────────────────────┴───────────────────────┘
 /// </pre>
 ///
@@ -27,6 +41,8 @@ use crate::world_generator::tile_type_options::OxAgTileTypeOptions;
 /// - [Default](enum.OxAgTileTypeSpawnPresets.html#variant.Default)
 /// - [WaterWorld](enum.OxAgTileTypeSpawnPresets.html#variant.WaterWorld)
 /// - [LowWaterWorld](enum.OxAgTileTypeSpawnPresets.html#variant.LowWaterWorld)
+/// - [Settled](enum.OxAgTileTypeSpawnPresets.html#variant.Settled)
+/// - [Volcanic](enum.OxAgTileTypeSpawnPresets.html#variant.Volcanic)
 #[derive(Copy, Clone, Debug)]
 pub enum OxAgTileTypePresets {
     /// # Default tile type spawn levels
@@ -46,6 +62,18 @@ pub enum OxAgTileTypePresets {
     /// │ street_len           │  6    ..= 15    │
     /// │ lava_n               │  2    ..=  2    │
     /// │ lava_radius          │  2    ..=  4    │
+    /// │ lava_host_tiles      │ Grass, Hill     │
+    /// │ lava_min_distance    │  4              │
+    /// │ lava_spawn_distance  │  8              │
+    /// │ lava_attempts        │  1000           │
+    /// │ settlement_n         │  0    ..=  0    │
+    /// │ settlement_radius    │  6    ..= 10    │
+    /// │ settlement_density   │  0.6            │
+    /// │ bridge_max_len       │  4              │
+    /// │ volcano_n            │  0    ..=  0    │
+    /// │ volcano_flows        │  1    ..=  3    │
+    /// │ volcano_flow_len     │ 10    ..= 25    │
+    /// │ volcano_crater_radius│  1    ..=  2    │
     /// └──────────────────────┴─────────────────┘
     /// </pre>
     /// [`PRESETS`](OxAgTileTypePresets)
@@ -68,6 +96,18 @@ pub enum OxAgTileTypePresets {
     /// │ street_len           │  6    ..= 15    │
     /// │ lava_n               │  1    ..=  2    │
     /// │ lava_radius          │  2    ..=  4    │
+    /// │ lava_host_tiles      │ Grass, Hill     │
+    /// │ lava_min_distance    │  2              │
+    /// │ lava_spawn_distance  │  6              │
+    /// │ lava_attempts        │  1000           │
+    /// │ settlement_n         │  0    ..=  0    │
+    /// │ settlement_radius    │  4    ..=  8    │
+    /// │ settlement_density   │  0.5            │
+    /// │ bridge_max_len       │  6              │
+    /// │ volcano_n            │  0    ..=  0    │
+    /// │ volcano_flows        │  1    ..=  2    │
+    /// │ volcano_flow_len     │  6    ..= 12    │
+    /// │ volcano_crater_radius│  1    ..=  2    │
     /// └──────────────────────┴─────────────────┘
     /// </pre>
     /// [`PRESETS`](OxAgTileTypePresets)
@@ -90,6 +130,18 @@ pub enum OxAgTileTypePresets {
     /// │ street_len           │ 16    ..= 25    │
     /// │ lava_n               │  0    ..=  3    │
     /// │ lava_radius          │  2    ..=  5    │
+    /// │ lava_host_tiles      │ Grass, Hill     │
+    /// │ lava_min_distance    │  3              │
+    /// │ lava_spawn_distance  │  8              │
+    /// │ lava_attempts        │  1000           │
+    /// │ settlement_n         │  0    ..=  0    │
+    /// │ settlement_radius    │  6    ..= 12    │
+    /// │ settlement_density   │  0.7            │
+    /// │ bridge_max_len       │  3              │
+    /// │ volcano_n            │  0    ..=  0    │
+    /// │ volcano_flows        │  1    ..=  3    │
+    /// │ volcano_flow_len     │ 15    ..= 30    │
+    /// │ volcano_crater_radius│  2    ..=  3    │
     /// └──────────────────────┴─────────────────┘
     /// </pre>
     /// [`PRESETS`](OxAgTileTypePresets)
@@ -112,79 +164,233 @@ pub enum OxAgTileTypePresets {
     /// │ street_len           │ 16    ..= 25    │
     /// │ lava_n               │  0    ..=  3    │
     /// │ lava_radius          │  2    ..=  5    │
+    /// │ lava_host_tiles      │ Grass, Hill     │
+    /// │ lava_min_distance    │  4              │
+    /// │ lava_spawn_distance  │  8              │
+    /// │ lava_attempts        │  1000           │
+    /// │ settlement_n         │  0    ..=  0    │
+    /// │ settlement_radius    │  0    ..=  0    │
+    /// │ settlement_density   │  0.0            │
+    /// │ bridge_max_len       │  0              │
+    /// │ volcano_n            │  0    ..=  0    │
+    /// │ volcano_flows        │  0    ..=  0    │
+    /// │ volcano_flow_len     │  0    ..=  0    │
+    /// │ volcano_crater_radius│  0    ..=  0    │
     /// └──────────────────────┴─────────────────┘
     /// </pre>
     /// [`PRESETS`](OxAgTileTypePresets)
     Hill,
+    ///
+    /// # Settled tile type spawn levels
+    /// The default levels, with settlements along the shores
+    /// <pre style="color: orange;">
+    /// ┌──────────────────────┬─────────────────┐
+    /// │     Parameter        │   Value Range   │
+    /// ├──────────────────────┼─────────────────┤
+    /// │ deep_water_level     │ -1.0  ..= -0.75 │
+    /// │ shallow_water_level  │ -0.75 ..= -0.5  │
+    /// │ sand_level           │ -0.5  ..= -0.25 │
+    /// │ grass_level          │ -0.25 ..=  0.25 │
+    /// │ hill_level           │  0.25 ..=  0.5  │
+    /// │ mountain_level       │  0.5  ..=  0.75 │
+    /// │ snow_level           │  0.75 ..=  1.0  │
+    /// │ river_n              │  1    ..=  4    │
+    /// │ street_n             │  1    ..=  3    │
+    /// │ street_len           │  6    ..= 15    │
+    /// │ lava_n               │  2    ..=  2    │
+    /// │ lava_radius          │  2    ..=  4    │
+    /// │ lava_host_tiles      │ Grass, Hill     │
+    /// │ lava_min_distance    │  4              │
+    /// │ lava_spawn_distance  │  8              │
+    /// │ lava_attempts        │  1000           │
+    /// │ settlement_n         │  1    ..=  2    │
+    /// │ settlement_radius    │  6    ..= 10    │
+    /// │ settlement_density   │  0.6            │
+    /// │ bridge_max_len       │  4              │
+    /// │ volcano_n            │  0    ..=  0    │
+    /// │ volcano_flows        │  1    ..=  3    │
+    /// │ volcano_flow_len     │ 10    ..= 25    │
+    /// │ volcano_crater_radius│  1    ..=  2    │
+    /// └──────────────────────┴─────────────────┘
+    /// </pre>
+    /// [`PRESETS`](OxAgTileTypePresets)
+    Settled,
+    ///
+    /// # Volcanic tile type spawn levels
+    /// The low water levels, with volcanoes on the mountain peaks
+    /// <pre style="color: orange;">
+    /// ┌──────────────────────┬─────────────────┐
+    /// │     Parameter        │   Value Range   │
+    /// ├──────────────────────┼─────────────────┤
+    /// │ deep_water_level     │ -1.0  ..= -0.8  │
+    /// │ shallow_water_level  │ -0.8  ..= -0.6  │
+    /// │ sand_level           │ -0.6  ..= -0.3  │
+    /// │ grass_level          │ -0.3  ..=  0.1  │
+    /// │ hill_level           │  0.1  ..=  0.4  │
+    /// │ mountain_level       │  0.4  ..=  0.7  │
+    /// │ snow_level           │  0.7  ..=  1.0  │
+    /// │ river_n              │  0    ..= 10    │
+    /// │ street_n             │  0    ..= 10    │
+    /// │ street_len           │ 16    ..= 25    │
+    /// │ lava_n               │  0    ..=  3    │
+    /// │ lava_radius          │  2    ..=  5    │
+    /// │ lava_host_tiles      │ Grass, Hill     │
+    /// │ lava_min_distance    │  3              │
+    /// │ lava_spawn_distance  │  8              │
+    /// │ lava_attempts        │  1000           │
+    /// │ settlement_n         │  0    ..=  0    │
+    /// │ settlement_radius    │  6    ..= 12    │
+    /// │ settlement_density   │  0.7            │
+    /// │ bridge_max_len       │  3              │
+    /// │ volcano_n            │  1    ..=  2    │
+    /// │ volcano_flows        │  1    ..=  3    │
+    /// │ volcano_flow_len     │ 15    ..= 30    │
+    /// │ volcano_crater_radius│  2    ..=  3    │
+    /// └──────────────────────┴─────────────────┘
+    /// </pre>
+    /// [`PRESETS`](OxAgTileTypePresets)
+    Volcanic,
 }
 
 impl Loadable<OxAgTileTypeOptions> for OxAgTileTypePresets {
     fn load(&self) -> OxAgTileTypeOptions {
         match self {
-            OxAgTileTypePresets::Default => DEFAULT,
-            OxAgTileTypePresets::WaterWorld => WATER_WORLD,
-            OxAgTileTypePresets::LowWaterWorld => LOW_WATER_WORLD,
-            OxAgTileTypePresets::Hill => HILL,
+            OxAgTileTypePresets::Default => default_options(),
+            OxAgTileTypePresets::WaterWorld => water_world(),
+            OxAgTileTypePresets::LowWaterWorld => low_water_world(),
+            OxAgTileTypePresets::Hill => hill(),
+            OxAgTileTypePresets::Settled => settled(),
+            OxAgTileTypePresets::Volcanic => volcanic(),
         }
     }
 }
 
-pub(crate) const DEFAULT: OxAgTileTypeOptions = OxAgTileTypeOptions {
-    deep_water_level: -1.0..=-0.75,
-    shallow_water_level: -0.75..=-0.5,
-    sand_level: -0.5..=-0.25,
-    grass_level: -0.25..=0.25,
-    hill_level: 0.25..=0.5,
-    mountain_level: 0.5..=0.75,
-    snow_level: 0.75..=1.0,
-    river_n: 1..=4,
-    street_n: 1..=3,
-    street_len: 6..=15,
-    lava_n: 2..=2,
-    lava_radius: 2..=4,
-};
+pub(crate) fn default_options() -> OxAgTileTypeOptions {
+    OxAgTileTypeOptions {
+        deep_water_level: -1.0..=-0.75,
+        shallow_water_level: -0.75..=-0.5,
+        sand_level: -0.5..=-0.25,
+        grass_level: -0.25..=0.25,
+        hill_level: 0.25..=0.5,
+        mountain_level: 0.5..=0.75,
+        snow_level: 0.75..=1.0,
+        river_n: 1..=4,
+        street_n: 1..=3,
+        street_len: 6..=15,
+        lava_n: 2..=2,
+        lava_radius: 2..=4,
+        lava_host_tiles: vec![Grass, Hill],
+        lava_min_distance: 4,
+        lava_spawn_distance: 8,
+        lava_attempts: DEFAULT_LAVA_ATTEMPTS,
+        settlement_n: 0..=0,
+        settlement_radius: 6..=10,
+        settlement_density: 0.6,
+        bridge_max_len: 4,
+        volcano_n: 0..=0,
+        volcano_flows: 1..=3,
+        volcano_flow_len: 10..=25,
+        volcano_crater_radius: 1..=2,
+    }
+}
 
-pub(crate) const WATER_WORLD: OxAgTileTypeOptions = OxAgTileTypeOptions {
-    deep_water_level: -1.0..=-0.5,
-    shallow_water_level: -0.5..=0.0,
-    sand_level: 0.0..=0.2,
-    grass_level: 0.2..=0.4,
-    hill_level: 0.4..=0.6,
-    mountain_level: 0.6..=0.8,
-    snow_level: 0.8..=1.0,
-    river_n: 1..=4,
-    street_n: 1..=3,
-    street_len: 6..=15,
-    lava_n: 1..=2,
-    lava_radius: 2..=4,
-};
+pub(crate) fn water_world() -> OxAgTileTypeOptions {
+    OxAgTileTypeOptions {
+        deep_water_level: -1.0..=-0.5,
+        shallow_water_level: -0.5..=0.0,
+        sand_level: 0.0..=0.2,
+        grass_level: 0.2..=0.4,
+        hill_level: 0.4..=0.6,
+        mountain_level: 0.6..=0.8,
+        snow_level: 0.8..=1.0,
+        river_n: 1..=4,
+        street_n: 1..=3,
+        street_len: 6..=15,
+        lava_n: 1..=2,
+        lava_radius: 2..=4,
+        lava_host_tiles: vec![Grass, Hill],
+        lava_min_distance: 2,
+        lava_spawn_distance: 6,
+        lava_attempts: DEFAULT_LAVA_ATTEMPTS,
+        settlement_n: 0..=0,
+        settlement_radius: 4..=8,
+        settlement_density: 0.5,
+        bridge_max_len: 6,
+        volcano_n: 0..=0,
+        volcano_flows: 1..=2,
+        volcano_flow_len: 6..=12,
+        volcano_crater_radius: 1..=2,
+    }
+}
 
-pub(crate) const LOW_WATER_WORLD: OxAgTileTypeOptions = OxAgTileTypeOptions {
-    deep_water_level: -1.0..=-0.8,
-    shallow_water_level: -0.8..=-0.6,
-    sand_level: -0.6..=-0.3,
-    grass_level: -0.3..=0.1,
-    hill_level: 0.1..=0.4,
-    mountain_level: 0.4..=0.7,
-    snow_level: 0.7..=1.0,
-    river_n: 0..=10,
-    street_n: 0..=10,
-    street_len: 16..=25,
-    lava_n: 0..=3,
-    lava_radius: 2..=5,
-};
+pub(crate) fn low_water_world() -> OxAgTileTypeOptions {
+    OxAgTileTypeOptions {
+        deep_water_level: -1.0..=-0.8,
+        shallow_water_level: -0.8..=-0.6,
+        sand_level: -0.6..=-0.3,
+        grass_level: -0.3..=0.1,
+        hill_level: 0.1..=0.4,
+        mountain_level: 0.4..=0.7,
+        snow_level: 0.7..=1.0,
+        river_n: 0..=10,
+        street_n: 0..=10,
+        street_len: 16..=25,
+        lava_n: 0..=3,
+        lava_radius: 2..=5,
+        lava_host_tiles: vec![Grass, Hill],
+        lava_min_distance: 3,
+        lava_spawn_distance: 8,
+        lava_attempts: DEFAULT_LAVA_ATTEMPTS,
+        settlement_n: 0..=0,
+        settlement_radius: 6..=12,
+        settlement_density: 0.7,
+        bridge_max_len: 3,
+        volcano_n: 0..=0,
+        volcano_flows: 1..=3,
+        volcano_flow_len: 15..=30,
+        volcano_crater_radius: 2..=3,
+    }
+}
 
-pub(crate) const HILL: OxAgTileTypeOptions = OxAgTileTypeOptions {
-    deep_water_level: -1.0..=-1.0,
-    shallow_water_level: -1.0..=-1.0,
-    sand_level: -1.0..=-1.0,
-    grass_level: -1.0..=-1.0,
-    hill_level: -1.0..=1.0,
-    mountain_level: 1.0..=1.0,
-    snow_level: 1.0..=1.0,
-    river_n: 0..=0,
-    street_n: 0..=0,
-    street_len: 16..=25,
-    lava_n: 0..=3,
-    lava_radius: 2..=5,
-};
+pub(crate) fn hill() -> OxAgTileTypeOptions {
+    OxAgTileTypeOptions {
+        deep_water_level: -1.0..=-1.0,
+        shallow_water_level: -1.0..=-1.0,
+        sand_level: -1.0..=-1.0,
+        grass_level: -1.0..=-1.0,
+        hill_level: -1.0..=1.0,
+        mountain_level: 1.0..=1.0,
+        snow_level: 1.0..=1.0,
+        river_n: 0..=0,
+        street_n: 0..=0,
+        street_len: 16..=25,
+        lava_n: 0..=3,
+        lava_radius: 2..=5,
+        lava_host_tiles: vec![Grass, Hill],
+        lava_min_distance: 4,
+        lava_spawn_distance: 8,
+        lava_attempts: DEFAULT_LAVA_ATTEMPTS,
+        settlement_n: 0..=0,
+        settlement_radius: 0..=0,
+        settlement_density: 0.0,
+        bridge_max_len: 0,
+        volcano_n: 0..=0,
+        volcano_flows: 0..=0,
+        volcano_flow_len: 0..=0,
+        volcano_crater_radius: 0..=0,
+    }
+}
+
+pub(crate) fn settled() -> OxAgTileTypeOptions {
+    OxAgTileTypeOptions {
+        settlement_n: 1..=2,
+        ..default_options()
+    }
+}
+
+pub(crate) fn volcanic() -> OxAgTileTypeOptions {
+    OxAgTileTypeOptions {
+        volcano_n: 1..=2,
+        ..low_water_world()
+    }
+}
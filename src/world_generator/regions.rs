@@ -0,0 +1,60 @@
+//! Regions of the map where a [Content] follows its own options,
+//! like more resources far from the spawn or no fire on the sand.
+
+use std::ops::RangeInclusive;
+
+use robotics_lib::world::tile::{Content, Tile, TileType};
+
+use crate::world_generator::content_options::OxAgContentOptions;
+
+/// Part of the map where some [OxAgContentOptions] take the place of the global ones
+#[derive(Debug, Clone, PartialEq)]
+pub enum OxAgRegion {
+    /// Every tile of these [TileType]s
+    TileTypes(Vec<TileType>),
+    /// The tiles between the two corners, as `(row, col)`, included
+    Rectangle {
+        /// A corner of the rectangle
+        from: (usize, usize),
+        /// The opposite corner of the rectangle
+        to: (usize, usize),
+    },
+    /// The tiles within `radius` of the `centre`, as `(row, col)`
+    Circle {
+        /// The centre of the circle
+        centre: (usize, usize),
+        /// The radius of the circle, in tiles
+        radius: usize,
+    },
+    /// The tiles whose (euclidean) distance from the robot spawn is in range
+    DistanceFromSpawn(RangeInclusive<usize>),
+}
+
+/// Options of a [Content] that only apply inside an [OxAgRegion]
+pub type OxAgRegionOption = (OxAgRegion, Content, OxAgContentOptions);
+
+impl OxAgRegion {
+    /// Whether the tile is part of the region
+    pub(crate) fn contains(
+        &self,
+        map: &[Vec<Tile>],
+        spawn: (usize, usize),
+        row: usize,
+        col: usize,
+    ) -> bool {
+        let distance = |(r, c): (usize, usize)| {
+            ((r.abs_diff(row).pow(2) + c.abs_diff(col).pow(2)) as f64).sqrt()
+        };
+        match self {
+            OxAgRegion::TileTypes(tile_types) => tile_types.contains(&map[row][col].tile_type),
+            OxAgRegion::Rectangle { from, to } => {
+                (from.0.min(to.0)..=from.0.max(to.0)).contains(&row)
+                    && (from.1.min(to.1)..=from.1.max(to.1)).contains(&col)
+            }
+            OxAgRegion::Circle { centre, radius } => distance(*centre) <= *radius as f64,
+            OxAgRegion::DistanceFromSpawn(range) => {
+                (*range.start() as f64..=*range.end() as f64).contains(&distance(spawn))
+            }
+        }
+    }
+}
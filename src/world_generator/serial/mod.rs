@@ -1,12 +1,10 @@
 use crate::world_generator::tile_type_options::OxAgTileTypeOptions;
 use crate::world_generator::world_generator_builder::OxAgWorldGeneratorBuilder;
-use crate::world_generator::OxAgWorldGenerator;
+use crate::world_generator::{OxAgWorld, OxAgWorldGenerator};
 use robotics_lib::world::environmental_conditions::EnvironmentalConditions;
 use robotics_lib::world::environmental_conditions::WeatherType::Sunny;
-use robotics_lib::world::tile::{Content, Tile};
 use robotics_lib::world::world_generator::Generator;
 use serde_json;
-use std::collections::HashMap;
 use std::fs::File;
 use std::io;
 use std::io::{Read, Write};
@@ -43,13 +41,7 @@ impl OxAgWorldGeneratorBuilder {
         let mut file = File::open(path)?;
         let mut contents = String::new();
         file.read_to_string(&mut contents)?;
-        let map_save: (
-            Vec<Vec<Tile>>,
-            (usize, usize),
-            EnvironmentalConditions,
-            f32,
-            Option<HashMap<Content, f32>>,
-        ) = serde_json::from_str(&contents)?;
+        let map_save: OxAgWorld = serde_json::from_str(&contents)?;
 
         Ok(OxAgWorldGenerator {
             size: map_save.0.len(),
@@ -67,6 +59,18 @@ impl OxAgWorldGeneratorBuilder {
                 street_len: 0..=0,
                 lava_n: 0..=0,
                 lava_radius: 0..=0,
+                lava_host_tiles: vec![],
+                lava_min_distance: 0,
+                lava_spawn_distance: 0,
+                lava_attempts: 0,
+                settlement_n: 0..=0,
+                settlement_radius: 0..=0,
+                settlement_density: 0.0,
+                bridge_max_len: 0,
+                volcano_n: 0..=0,
+                volcano_flows: 0..=0,
+                volcano_flow_len: 0..=0,
+                volcano_crater_radius: 0..=0,
             },
             environmental_conditions: EnvironmentalConditions::new(&[Sunny], 2, 2).unwrap(),
             map_save: Some(map_save),
@@ -74,8 +78,25 @@ impl OxAgWorldGeneratorBuilder {
             score: 0.0,
             with_info: false,
             content_options: vec![],
+            region_options: vec![],
+            spawn_strategy: Default::default(),
+            connectivity_options: None,
             maze: false,
+            maze_algorithm: Default::default(),
+            maze_braid: 0.0,
+            maze_loops: 0,
+            maze_goal_percentile: 1.0,
+            maze_solution: None,
+            maze_difficulty: None,
+            maze_metrics: None,
+            dungeon_options: None,
+            dungeon_layout: None,
+            teleport_options: None,
+            teleport_report: None,
             score_map: None,
+            placement_report: vec![],
+            spawn_report: None,
+            connectivity_report: None,
         })
     }
 }
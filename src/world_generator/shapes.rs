@@ -0,0 +1,375 @@
+//! Shapes that can be stamped on a map.
+//!
+//! Every shape stamps a `(Option<Content>, Option<TileType>)` target, following the same
+//! rules used by the generator:
+//! * a [Content] is only placed on the tiles that can hold it, with a random value;
+//! * a [TileType] replaces every tile but the water and teleport ones, clearing its content.
+//!
+//! They can be used to post-process a generated world.
+//!
+//! # Example
+//! ```rust
+//! use oxagworldgenerator::world_generator::shapes::OxAgShape;
+//! use oxagworldgenerator::world_generator::world_generator_builder::OxAgWorldGeneratorBuilder;
+//! use rand::thread_rng;
+//! use robotics_lib::world::tile::TileType;
+//! use robotics_lib::world::world_generator::Generator;
+//!
+//! let mut map = OxAgWorldGeneratorBuilder::new()
+//!     .set_size(64)
+//!     .build()
+//!     .unwrap()
+//!     .gen()
+//!     .0;
+//!
+//! OxAgShape::Line {
+//!     from: (0, 0),
+//!     to: (63, 63),
+//! }
+//! .stamp(&mut map, &mut thread_rng(), &(None, Some(TileType::Street)));
+//! ```
+
+use std::collections::HashSet;
+
+use noise::{NoiseFn, Perlin};
+use rand::Rng;
+use robotics_lib::world::tile::TileType::{DeepWater, ShallowWater, Teleport};
+use robotics_lib::world::tile::{Content, Tile, TileType};
+
+/// Shapes that can be stamped on a map.
+///
+/// Coordinates are `(row, col)`, the parts of a shape that fall outside the map are ignored.
+#[derive(Debug, Clone)]
+pub enum OxAgShape {
+    /// Circle around `centre`, outlined or filled
+    Circle {
+        /// Centre of the circle
+        centre: (usize, usize),
+        /// Radius of the circle
+        radius: usize,
+        /// Whether the inside of the circle is stamped too
+        filled: bool,
+    },
+    /// Ellipse around `centre`, outlined or filled
+    Ellipse {
+        /// Centre of the ellipse
+        centre: (usize, usize),
+        /// Vertical and horizontal radius of the ellipse
+        radii: (usize, usize),
+        /// Whether the inside of the ellipse is stamped too
+        filled: bool,
+    },
+    /// Rectangle between two opposite corners, outlined or filled
+    Rectangle {
+        /// First corner of the rectangle
+        from: (usize, usize),
+        /// Opposite corner of the rectangle
+        to: (usize, usize),
+        /// Whether the inside of the rectangle is stamped too
+        filled: bool,
+    },
+    /// Closed polygon, outlined or filled
+    Polygon {
+        /// Vertices of the polygon, the last one is connected to the first one
+        vertices: Vec<(usize, usize)>,
+        /// Whether the inside of the polygon is stamped too
+        filled: bool,
+    },
+    /// Straight line between two points (Bresenham)
+    Line {
+        /// Start of the line
+        from: (usize, usize),
+        /// End of the line
+        to: (usize, usize),
+    },
+    /// Ring around `centre`, between the two radii
+    Ring {
+        /// Centre of the ring
+        centre: (usize, usize),
+        /// Radius of the hole, excluded
+        inner_radius: usize,
+        /// Radius of the ring, included
+        outer_radius: usize,
+    },
+    /// Organic blob around `centre`, shaped by a Perlin noise
+    NoiseBlob {
+        /// Centre of the blob
+        centre: (usize, usize),
+        /// Maximum radius of the blob
+        radius: usize,
+        /// Seed of the noise
+        seed: u64,
+        /// Higher values give smaller and more jagged blobs, `0.5` is a good starting point
+        threshold: f64,
+    },
+}
+
+impl OxAgShape {
+    /// Returns the cells of a `size`x`size` map covered by this shape, without duplicates.
+    pub fn cells(&self, size: usize) -> Vec<(usize, usize)> {
+        let cells = match self {
+            OxAgShape::Circle {
+                centre,
+                radius,
+                filled,
+            } => {
+                let mut cells = circle_outline(*centre, *radius);
+                if *filled {
+                    cells.extend(disc(*centre, *radius, |d2, r2| d2 <= r2));
+                }
+                cells
+            }
+            OxAgShape::Ellipse {
+                centre,
+                radii,
+                filled,
+            } => {
+                let (a, b) = (radii.0.max(1) as f64, radii.1.max(1) as f64);
+                let inside = |r: isize, c: isize| {
+                    let (dr, dc) = (r - centre.0 as isize, c - centre.1 as isize);
+                    (dr as f64 / a).powi(2) + (dc as f64 / b).powi(2) <= 1.0
+                };
+                let cells = area(*centre, radii.0.max(radii.1), inside);
+                if *filled {
+                    cells
+                } else {
+                    outline(cells, inside)
+                }
+            }
+            OxAgShape::Rectangle { from, to, filled } => {
+                let (top, bottom) = (from.0.min(to.0) as isize, from.0.max(to.0) as isize);
+                let (left, right) = (from.1.min(to.1) as isize, from.1.max(to.1) as isize);
+                (top..=bottom)
+                    .flat_map(|r| (left..=right).map(move |c| (r, c)))
+                    .filter(|&(r, c)| *filled || r == top || r == bottom || c == left || c == right)
+                    .collect()
+            }
+            OxAgShape::Polygon { vertices, filled } => {
+                let mut cells: Vec<(isize, isize)> = vertices
+                    .iter()
+                    .zip(vertices.iter().cycle().skip(1))
+                    .flat_map(|(from, to)| line(*from, *to))
+                    .collect();
+                if *filled && vertices.len() > 2 {
+                    let (top, bottom) = bounds(vertices.iter().map(|v| v.0));
+                    let (left, right) = bounds(vertices.iter().map(|v| v.1));
+                    for r in top..=bottom {
+                        for c in left..=right {
+                            if in_polygon(vertices, r as f64, c as f64) {
+                                cells.push((r as isize, c as isize));
+                            }
+                        }
+                    }
+                }
+                cells
+            }
+            OxAgShape::Line { from, to } => line(*from, *to),
+            OxAgShape::Ring {
+                centre,
+                inner_radius,
+                outer_radius,
+            } => {
+                let inner = inner_radius.pow(2) as isize;
+                disc(*centre, *outer_radius, |d2, r2| inner < d2 && d2 <= r2)
+            }
+            OxAgShape::NoiseBlob {
+                centre,
+                radius,
+                seed,
+                threshold,
+            } => {
+                let perlin = Perlin::new(*seed as u32);
+                let radius = (*radius).max(1) as f64;
+                let mut cells = area(*centre, radius as usize, |r, c| {
+                    let (dr, dc) = (
+                        (r - centre.0 as isize) as f64,
+                        (c - centre.1 as isize) as f64,
+                    );
+                    let falloff = 1.0 - (dr.powi(2) + dc.powi(2)).sqrt() / radius;
+                    falloff >= 0.0
+                        && perlin.get([dr / radius, dc / radius]) * 0.5 + falloff > *threshold
+                });
+                cells.push((centre.0 as isize, centre.1 as isize));
+                cells
+            }
+        };
+
+        let mut seen = HashSet::new();
+        cells
+            .into_iter()
+            .filter(|&(r, c)| r >= 0 && c >= 0 && (r as usize) < size && (c as usize) < size)
+            .map(|(r, c)| (r as usize, c as usize))
+            .filter(|cell| seen.insert(*cell))
+            .collect()
+    }
+
+    /// Stamps the `target` on every cell of the `map` covered by this shape.
+    ///
+    /// See [stamp_tile] for the rules that apply to each cell.
+    pub fn stamp<R: Rng + ?Sized>(
+        &self,
+        map: &mut [Vec<Tile>],
+        rng: &mut R,
+        target: &(Option<Content>, Option<TileType>),
+    ) {
+        for (row, col) in self.cells(map.len()) {
+            stamp_tile(map, rng, row, col, target);
+        }
+    }
+}
+
+/// Stamps the `target` on a single tile of the `map`.
+///
+/// * `(Some(content), None)` places the [Content] with a random value if the tile can hold it;
+/// * `(None, Some(tile_type))` replaces the [TileType] and clears the content,
+///   unless the tile is water or a teleport.
+///
+/// Returns whether the tile was changed.
+pub fn stamp_tile<R: Rng + ?Sized>(
+    map: &mut [Vec<Tile>],
+    rng: &mut R,
+    row: usize,
+    col: usize,
+    target: &(Option<Content>, Option<TileType>),
+) -> bool {
+    if row >= map.len() || col >= map[row].len() {
+        return false;
+    }
+    match target {
+        (Some(content), None) => {
+            let mut value = 0;
+            if content.properties().max() != 0 {
+                value = rng.gen_range(0..content.properties().max());
+            }
+            if map[row][col].tile_type.properties().can_hold(content) {
+                map[row][col].content = content.to_value(value);
+                return true;
+            }
+            false
+        }
+        (None, Some(tile_type)) => {
+            if ![ShallowWater, DeepWater, Teleport(false)].contains(&map[row][col].tile_type) {
+                map[row][col].tile_type = *tile_type;
+                map[row][col].content = Content::None;
+                return true;
+            }
+            false
+        }
+        _ => false,
+    }
+}
+
+// Midpoint circle algorithm
+fn circle_outline(centre: (usize, usize), radius: usize) -> Vec<(isize, isize)> {
+    let (cr, cc) = (centre.0 as isize, centre.1 as isize);
+    let mut cells = vec![];
+    let mut x = radius as isize;
+    let mut y = 0;
+    let mut decision = 1 - x;
+    while x >= y {
+        cells.extend([
+            (cr + x, cc + y),
+            (cr + y, cc + x),
+            (cr - y, cc + x),
+            (cr - x, cc + y),
+            (cr - x, cc - y),
+            (cr - y, cc - x),
+            (cr + y, cc - x),
+            (cr + x, cc - y),
+        ]);
+        y += 1;
+        if decision <= 0 {
+            decision += 2 * y + 1;
+        } else {
+            x -= 1;
+            decision += 2 * (y - x) + 1;
+        }
+    }
+    cells
+}
+
+// Cells around the centre whose squared distance satisfies `keep(distance², radius²)`
+fn disc(
+    centre: (usize, usize),
+    radius: usize,
+    keep: impl Fn(isize, isize) -> bool,
+) -> Vec<(isize, isize)> {
+    let (cr, cc) = (centre.0 as isize, centre.1 as isize);
+    let r2 = radius.pow(2) as isize;
+    area(centre, radius, |r, c| {
+        keep((r - cr).pow(2) + (c - cc).pow(2), r2)
+    })
+}
+
+// Cells of the square around the centre that satisfy `inside`
+fn area(
+    centre: (usize, usize),
+    radius: usize,
+    inside: impl Fn(isize, isize) -> bool,
+) -> Vec<(isize, isize)> {
+    let (cr, cc, radius) = (centre.0 as isize, centre.1 as isize, radius as isize);
+    (cr - radius..=cr + radius)
+        .flat_map(|r| (cc - radius..=cc + radius).map(move |c| (r, c)))
+        .filter(|&(r, c)| inside(r, c))
+        .collect()
+}
+
+// Cells of the shape with at least one neighbour outside of it
+fn outline(
+    cells: Vec<(isize, isize)>,
+    inside: impl Fn(isize, isize) -> bool,
+) -> Vec<(isize, isize)> {
+    cells
+        .into_iter()
+        .filter(|&(r, c)| {
+            [(-1, 0), (0, 1), (1, 0), (0, -1)]
+                .iter()
+                .any(|(dr, dc)| !inside(r + dr, c + dc))
+        })
+        .collect()
+}
+
+// Bresenham line algorithm
+fn line(from: (usize, usize), to: (usize, usize)) -> Vec<(isize, isize)> {
+    let (mut r, mut c) = (from.0 as isize, from.1 as isize);
+    let (to_r, to_c) = (to.0 as isize, to.1 as isize);
+    let (dr, dc) = ((to_r - r).abs(), -(to_c - c).abs());
+    let (step_r, step_c) = ((to_r - r).signum(), (to_c - c).signum());
+    let mut error = dr + dc;
+    let mut cells = vec![];
+    loop {
+        cells.push((r, c));
+        if r == to_r && c == to_c {
+            break;
+        }
+        let double = 2 * error;
+        if double >= dc {
+            error += dc;
+            r += step_r;
+        }
+        if double <= dr {
+            error += dr;
+            c += step_c;
+        }
+    }
+    cells
+}
+
+fn bounds(values: impl Iterator<Item = usize>) -> (usize, usize) {
+    values.fold((usize::MAX, 0), |(min, max), v| (min.min(v), max.max(v)))
+}
+
+// Even-odd rule
+fn in_polygon(vertices: &[(usize, usize)], row: f64, col: f64) -> bool {
+    let mut inside = false;
+    let mut j = vertices.len() - 1;
+    for i in 0..vertices.len() {
+        let (ri, ci) = (vertices[i].0 as f64, vertices[i].1 as f64);
+        let (rj, cj) = (vertices[j].0 as f64, vertices[j].1 as f64);
+        if (ri > row) != (rj > row) && col < (cj - ci) * (row - ri) / (rj - ri) + ci {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
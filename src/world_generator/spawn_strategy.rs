@@ -0,0 +1,72 @@
+//! Strategies used to choose the robot spawn point and the report of the chosen one.
+
+use robotics_lib::world::tile::{Content, TileType};
+
+/// How the robot spawn point is chosen among the walkable tiles
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum OxAgSpawnStrategy {
+    /// A random walkable tile, away from the lava lakes when possible
+    #[default]
+    Random,
+    /// The walkable tile closest to the centre of the map
+    CentreMost,
+    /// A random tile of the largest connected walkable region, so the robot
+    /// doesn't start on a tiny island
+    LargestRegion,
+    /// A random walkable tile at least `min_distance` tiles away from the given
+    /// [TileType]s and [Content]s, e.g. `Lava`, `DeepWater` and `Fire`
+    AwayFrom {
+        /// The [TileType]s to keep away from
+        tile_types: Vec<TileType>,
+        /// The [Content]s to keep away from
+        contents: Vec<Content>,
+        /// The minimum distance, in tiles and diagonals included, from any of them
+        min_distance: usize,
+    },
+    /// The given `(row, col)`, or the walkable tile closest to it
+    Fixed((usize, usize)),
+    /// A walkable tile from which at least `count` tiles holding `content`
+    /// can be reached within `max_steps` steps.
+    /// Every walkable tile is considered: if none reaches `count`, the one reaching the most wins.
+    Resources {
+        /// The [Content] to reach
+        content: Content,
+        /// How many tiles holding the [Content] have to be reached
+        count: usize,
+        /// The maximum number of steps, walking, to reach them
+        max_steps: usize,
+    },
+}
+
+impl OxAgSpawnStrategy {
+    /// Whether the strategy can move the spawn once the contents are placed
+    pub(crate) fn depends_on_contents(&self) -> bool {
+        match self {
+            OxAgSpawnStrategy::AwayFrom { contents, .. } => !contents.is_empty(),
+            OxAgSpawnStrategy::Resources { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+/// Diagnostics of the spawn point chosen for the last generated world
+#[derive(Debug, Clone)]
+pub struct OxAgSpawnReport {
+    /// The strategy that was used
+    pub strategy: OxAgSpawnStrategy,
+    /// The chosen spawn point, as `(row, col)`
+    pub spawn: (usize, usize),
+    /// Whether the spawn point follows the strategy,
+    /// otherwise the closest or best tile found was used
+    pub satisfied: bool,
+    /// Whether the spawn point was moved once the contents were placed.
+    ///
+    /// The spawn is chosen before the contents (the regions can depend on it),
+    /// the strategies that depend on the contents are checked again afterwards.
+    pub moved_after_contents: bool,
+    /// The number of tiles of the connected walkable region of the spawn point
+    pub region_size: usize,
+    /// The number of resources reachable from the spawn point, for the
+    /// [Resources](OxAgSpawnStrategy::Resources) strategy
+    pub reachable_resources: Option<usize>,
+}
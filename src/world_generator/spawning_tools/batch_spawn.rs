@@ -4,10 +4,12 @@ use rand::RngCore;
 use robotics_lib::world::tile::Content;
 use std::cmp::max;
 
-use crate::utils::constants::DEFAULT_BATCH_DISTANCE;
-use crate::world_generator::content_options::OxAgContentOptions;
+use crate::utils::constants::{DEFAULT_BATCH_DENSITY, DEFAULT_BATCH_DISTANCE};
+use crate::world_generator::content_options::{OxAgBatchShape, OxAgContentOptions};
+use crate::world_generator::placement_report::OxAgContentPlacement;
+use crate::world_generator::placement_report::OxAgPlacementFailure::{Clipped, NoValidTile};
 use crate::world_generator::spawning_tools::matrix_spawn::f64_mat;
-use crate::world_generator::spawning_tools::TileMat;
+use crate::world_generator::spawning_tools::{F64MatData, TileMat};
 
 impl TileMat {
     // pub(crate) fn spawn_batches(
@@ -63,9 +65,9 @@ impl TileMat {
         content_option: &OxAgContentOptions,
         percentage: f64,
         rng: &mut StdRng,
-    ) {
+        report: &mut OxAgContentPlacement,
+    ) -> Vec<(usize, usize)> {
         let max_rad = max(1, content_option.max_radius) as f64;
-        let radius = max_rad;
         let max_spawn_number = if content_option.with_max_spawn_number {
             content_option.max_spawn_number
         } else {
@@ -78,55 +80,162 @@ impl TileMat {
             rng.gen_range(content_option.min_spawn_number..=max)
         };
 
+        let candidates = self.placement_candidates(content, content_option);
+        if candidates.is_empty() {
+            if self.with_info {
+                println!("No tile satisfies the placement rules of {:?}", content);
+            }
+            // Without a centre every batch fails, each counting as a requested tile
+            report.requested = max_spawn_number;
+            report.reasons.push(NoValidTile);
+            return vec![];
+        }
+        // Every tile covered by the batches, before the placement rules filter them
+        let mut covered = vec![];
+        let mut positions = vec![];
         for _ in 0..max_spawn_number {
-            let size = rng.gen_range(0..=radius as usize) * 2;
-            let center = size as f64 / 2.0;
-            let batches_noise = f64_mat(self.seed + rng.next_u32() as u64, size, false);
+            // Between half and the whole max radius
+            let radius = rng.gen_range((max_rad as usize / 2).max(1)..=max_rad as usize);
+            let batches_noise = f64_mat(self.seed + rng.next_u32() as u64, radius * 2 + 1, false);
 
-            //
-            let (mut row, mut col) = (rng.gen_range(0..self.size), rng.gen_range(0..self.size));
-            while !self.map[row][col].tile_type.properties().can_hold(content) {
-                (row, col) = (rng.gen_range(0..self.size), rng.gen_range(0..self.size));
-            }
+            let (row, col) = candidates[rng.gen_range(0..candidates.len())];
+            let direction = self.contour_direction(row, col, rng);
+            // In mazes the batches follow the corridors instead of crossing the walls
+            let along_corridors = self
+                .corridors
+                .as_ref()
+                .map(|_| self.corridor_distances((row, col), radius));
 
-            // println!("{:?}", radius);
+            for tmp_row in 0..=radius * 2 {
+                for tmp_col in 0..=radius * 2 {
+                    let (Some(new_row), Some(new_col)) = (
+                        (row + tmp_row).checked_sub(radius),
+                        (col + tmp_col).checked_sub(radius),
+                    ) else {
+                        continue;
+                    };
+                    if new_row >= self.size
+                        || new_col >= self.size
+                        || along_corridors
+                            .as_ref()
+                            .is_some_and(|distances| distances[tmp_row][tmp_col] > radius)
+                    {
+                        continue;
+                    }
+                    let offset = (
+                        tmp_row as f64 - radius as f64,
+                        tmp_col as f64 - radius as f64,
+                    );
+                    let density = batch_density(
+                        &content_option.batch_shape,
+                        offset,
+                        radius as f64,
+                        direction,
+                        &batches_noise,
+                        (tmp_row, tmp_col),
+                    );
+                    if density <= 0.0 || !rng.gen_bool(density.min(1.0)) {
+                        continue;
+                    }
+                    covered.push((new_row, new_col));
+                    if self.can_place(new_row, new_col, content, content_option)
+                        && self.can_overwrite(new_row, new_col, content_option)
+                    {
+                        let closeness =
+                            1.0 - (offset.0.powi(2) + offset.1.powi(2)).sqrt() / radius as f64;
+                        self.map[new_row][new_col].content = content_option.random_value(
+                            content,
+                            rng,
+                            Some(closeness.clamp(0.0, 1.0)),
+                        );
+                        self.priorities[new_row][new_col] = Some(content_option.priority);
+                        positions.push((new_row, new_col));
+                    }
+                }
+            }
+        }
+        covered.sort_unstable();
+        covered.dedup();
+        report.requested = covered.len();
+        positions.sort_unstable();
+        positions.dedup();
+        if positions.len() < covered.len() {
+            if self.with_info {
+                println!(
+                    "Only {} of the {} tiles covered by the batches of {:?} can hold it",
+                    positions.len(),
+                    covered.len(),
+                    content
+                );
+            }
+            report.reasons.push(Clipped);
+        }
+        positions
+    }
 
-            batches_noise
-                .map
-                .iter()
-                .enumerate()
-                .for_each(|(tmp_row, rows)| {
-                    rows.iter().enumerate().for_each(|(tmp_col, _cell)| {
-                        if !((row as i32 + tmp_row as i32 - radius as i32) < 0
-                            || (col as i32 + tmp_col as i32 - radius as i32) < 0
-                            || ((row as i32 + tmp_row as i32 - radius as i32) as usize)
-                                >= self.size
-                            || ((col as i32 + tmp_col as i32 - radius as i32) as usize)
-                                >= self.size)
-                        {
-                            let is_in_circle = (tmp_row as f64 - center).powi(2)
-                                + (tmp_col as f64 - center).powi(2)
-                                <= (radius).powi(2);
+    // Direction of the contour line of the height map, as (row, col) unit vector:
+    // streaks follow it like veins along a slope. Random on flat ground.
+    fn contour_direction(&self, row: usize, col: usize, rng: &mut StdRng) -> (f64, f64) {
+        let height = |r: usize, c: usize| {
+            self.heights
+                .get(r.min(self.size - 1))
+                .and_then(|heights| heights.get(c.min(self.size - 1)))
+                .copied()
+                .unwrap_or_default()
+        };
+        let gradient = (
+            height(row + 1, col) - height(row.saturating_sub(1), col),
+            height(row, col + 1) - height(row, col.saturating_sub(1)),
+        );
+        let length = (gradient.0.powi(2) + gradient.1.powi(2)).sqrt();
+        if length < f64::EPSILON {
+            let angle = rng.gen_range(0.0..std::f64::consts::TAU);
+            (angle.sin(), angle.cos())
+        } else {
+            (-gradient.1 / length, gradient.0 / length)
+        }
+    }
+}
 
-                            if is_in_circle {
-                                let (row, col) = (
-                                    row + tmp_row - radius as usize,
-                                    col + tmp_col - radius as usize,
-                                );
-                                if self.map[row][col].tile_type.properties().can_hold(content)
-                                    && rng.gen_bool(0.7)
-                                {
-                                    let value: usize = if row > content.properties().max() {
-                                        content.properties().max()
-                                    } else {
-                                        rng.gen_range(row..=content.properties().max())
-                                    };
-                                    self.map[row][col].content = content.to_value(value);
-                                }
-                            }
-                        }
-                    })
-                })
+/// Probability for a tile of the batch to hold the content,
+/// given its offset from the batch centre
+fn batch_density(
+    shape: &OxAgBatchShape,
+    offset: (f64, f64),
+    radius: f64,
+    direction: (f64, f64),
+    noise: &F64MatData,
+    cell: (usize, usize),
+) -> f64 {
+    let distance = (offset.0.powi(2) + offset.1.powi(2)).sqrt();
+    match *shape {
+        OxAgBatchShape::Disc if distance <= radius => DEFAULT_BATCH_DENSITY,
+        OxAgBatchShape::NoiseBlob { threshold } if distance <= radius => {
+            let value = (noise.map[cell.0][cell.1].0 - noise.min) / (noise.max - noise.min);
+            // The blob fades towards the border of the batch
+            if value * (1.0 - (distance / radius).powi(2)) >= threshold {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        OxAgBatchShape::GaussianFalloff { sigma } => {
+            (-(distance / radius).powi(2) / (2.0 * sigma.powi(2))).exp()
+        }
+        OxAgBatchShape::Ring { thickness }
+            if distance <= radius && distance > radius - thickness as f64 =>
+        {
+            DEFAULT_BATCH_DENSITY
+        }
+        OxAgBatchShape::Streak { width } => {
+            let along = offset.0 * direction.0 + offset.1 * direction.1;
+            let across = offset.0 * direction.1 - offset.1 * direction.0;
+            if along.abs() <= radius && across.abs() <= width as f64 / 2.0 {
+                DEFAULT_BATCH_DENSITY
+            } else {
+                0.0
+            }
         }
+        _ => 0.0,
     }
 }
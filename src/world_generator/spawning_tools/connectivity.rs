@@ -0,0 +1,293 @@
+use std::collections::VecDeque;
+
+use robotics_lib::world::tile::{Content, Tile, TileType};
+
+use crate::utils::errors::OxAgError::ConnectivityRepairFailed;
+use crate::world_generator::connectivity::{OxAgConnectivityOptions, OxAgConnectivityReport};
+use crate::world_generator::spawning_tools::TileMat;
+
+/// Connected regions of walkable tiles, moving in the 4 directions
+pub(crate) struct WalkableRegions {
+    /// Region of every tile, [None] if the tile is not walkable
+    pub(crate) labels: Vec<Vec<Option<usize>>>,
+    /// Number of tiles of every region
+    pub(crate) sizes: Vec<usize>,
+}
+
+impl WalkableRegions {
+    pub(crate) fn new(map: &[Vec<Tile>]) -> Self {
+        let size = map.len();
+        let mut labels = vec![vec![None; size]; size];
+        let mut sizes = vec![];
+        for row in 0..size {
+            for col in 0..size {
+                if labels[row][col].is_some() || !map[row][col].tile_type.properties().walk() {
+                    continue;
+                }
+                let label = sizes.len();
+                labels[row][col] = Some(label);
+                let mut region_size = 0;
+                let mut queue = VecDeque::from([(row, col)]);
+                while let Some((r, c)) = queue.pop_front() {
+                    region_size += 1;
+                    for (nr, nc) in steps(size, r, c) {
+                        if labels[nr][nc].is_none() && map[nr][nc].tile_type.properties().walk() {
+                            labels[nr][nc] = Some(label);
+                            queue.push_back((nr, nc));
+                        }
+                    }
+                }
+                sizes.push(region_size);
+            }
+        }
+        Self { labels, sizes }
+    }
+
+    /// Number of tiles of the region of the tile, 0 if it's not walkable
+    pub(crate) fn size_of(&self, row: usize, col: usize) -> usize {
+        self.labels[row][col].map_or(0, |label| self.sizes[label])
+    }
+
+    /// The largest region, if any tile is walkable
+    pub(crate) fn largest(&self) -> Option<usize> {
+        (0..self.sizes.len()).max_by_key(|&label| self.sizes[label])
+    }
+}
+
+/// Tiles whose removal splits their connected region of `passable` tiles in two
+pub(crate) fn articulation_points(
+    map: &[Vec<Tile>],
+    passable: impl Fn(&Tile) -> bool,
+) -> Vec<Vec<bool>> {
+    let size = map.len();
+    let mut points = vec![vec![false; size]; size];
+    let mut discovery = vec![vec![usize::MAX; size]; size];
+    let mut low = vec![vec![usize::MAX; size]; size];
+    let mut parents: Vec<Vec<Option<(usize, usize)>>> = vec![vec![None; size]; size];
+    let mut time = 0;
+    for root in (0..size).flat_map(|row| (0..size).map(move |col| (row, col))) {
+        if discovery[root.0][root.1] != usize::MAX || !passable(&map[root.0][root.1]) {
+            continue;
+        }
+        discovery[root.0][root.1] = time;
+        low[root.0][root.1] = time;
+        time += 1;
+        let mut root_children = 0;
+        // Iterative depth-first search, with the index of the next step to try
+        let mut stack = vec![(root, 0)];
+        while let Some(&((row, col), step)) = stack.last() {
+            let next_steps = steps(size, row, col);
+            if let Some(&(r, c)) = next_steps.get(step) {
+                if let Some(last) = stack.last_mut() {
+                    last.1 += 1;
+                }
+                if !passable(&map[r][c]) {
+                    continue;
+                }
+                if discovery[r][c] == usize::MAX {
+                    parents[r][c] = Some((row, col));
+                    discovery[r][c] = time;
+                    low[r][c] = time;
+                    time += 1;
+                    if (row, col) == root {
+                        root_children += 1;
+                    }
+                    stack.push(((r, c), 0));
+                } else if parents[row][col] != Some((r, c)) {
+                    low[row][col] = low[row][col].min(discovery[r][c]);
+                }
+            } else {
+                stack.pop();
+                if let Some((parent_row, parent_col)) = parents[row][col] {
+                    low[parent_row][parent_col] = low[parent_row][parent_col].min(low[row][col]);
+                    if (parent_row, parent_col) != root
+                        && low[row][col] >= discovery[parent_row][parent_col]
+                    {
+                        points[parent_row][parent_col] = true;
+                    }
+                }
+            }
+        }
+        points[root.0][root.1] = root_children > 1;
+    }
+    points
+}
+
+/// The tiles one step away in the 4 directions
+pub(crate) fn steps(size: usize, row: usize, col: usize) -> Vec<(usize, usize)> {
+    let mut out = vec![];
+    if row > 0 {
+        out.push((row - 1, col));
+    }
+    if col + 1 < size {
+        out.push((row, col + 1));
+    }
+    if row + 1 < size {
+        out.push((row + 1, col));
+    }
+    if col > 0 {
+        out.push((row, col - 1));
+    }
+    out
+}
+
+/// Tiles reached walking from `start` within `max_steps`, with their distance.
+///
+/// The last step can land on a tile that isn't walkable, since robots interact
+/// with the tiles next to them.
+pub(crate) fn reachable(
+    map: &[Vec<Tile>],
+    start: (usize, usize),
+    max_steps: usize,
+) -> Vec<((usize, usize), usize)> {
+    let size = map.len();
+    let mut distances = vec![vec![usize::MAX; size]; size];
+    distances[start.0][start.1] = 0;
+    let mut queue = VecDeque::from([start]);
+    let mut out = vec![];
+    while let Some((row, col)) = queue.pop_front() {
+        let distance = distances[row][col];
+        out.push(((row, col), distance));
+        if distance == max_steps
+            || ((row, col) != start && !map[row][col].tile_type.properties().walk())
+        {
+            continue;
+        }
+        for (r, c) in steps(size, row, col) {
+            if distances[r][c] == usize::MAX {
+                distances[r][c] = distance + 1;
+                queue.push_back((r, c));
+            }
+        }
+    }
+    out
+}
+
+impl TileMat {
+    /// Labels the walkable regions and, when there are options, carves paths from the region
+    /// of the spawn until enough of the walkable tiles are reachable
+    pub(crate) fn connect(
+        &mut self,
+        spawn: (usize, usize),
+        connectivity_options: Option<&OxAgConnectivityOptions>,
+    ) {
+        let mut regions = WalkableRegions::new(&self.map);
+        let mut components = regions.sizes.clone();
+        components.sort_unstable_by(|a, b| b.cmp(a));
+        let walkable = components.iter().sum::<usize>();
+        let mut report = OxAgConnectivityReport {
+            walkable,
+            components,
+            reachable_before: regions.size_of(spawn.0, spawn.1),
+            reachable_after: regions.size_of(spawn.0, spawn.1),
+            carved: 0,
+        };
+
+        if let Some(options) = connectivity_options {
+            let enough = |reachable: usize, walkable: usize| {
+                reachable as f64 >= options.min_reachable * walkable as f64
+            };
+            let mut paths = 0;
+            while !enough(report.reachable_after, report.walkable) && paths < options.max_paths {
+                let Some(path) = path_to_unreachable(&self.map, &regions, spawn) else {
+                    break;
+                };
+                for (row, col) in path {
+                    let tile = &mut self.map[row][col];
+                    if !tile.tile_type.properties().walk() {
+                        tile.tile_type = options.path_tile_type;
+                        if !tile.tile_type.properties().can_hold(&tile.content) {
+                            tile.content = Content::None;
+                        }
+                        report.carved += 1;
+                        report.walkable += 1;
+                    }
+                }
+                paths += 1;
+                regions = WalkableRegions::new(&self.map);
+                report.reachable_after = regions.size_of(spawn.0, spawn.1);
+            }
+            if self.with_info {
+                println!(
+                    "Reachable tiles: {} of {}, {} carved",
+                    report.reachable_after, report.walkable, report.carved
+                );
+            }
+            if !enough(report.reachable_after, report.walkable) {
+                self.errors.push(ConnectivityRepairFailed);
+            }
+        }
+        self.connectivity_report = Some(report);
+    }
+}
+
+// Path from the region of the spawn to the unreachable region with the most tiles gained
+// for every carved tile, never going through the walls of mazes and dungeons
+fn path_to_unreachable(
+    map: &[Vec<Tile>],
+    regions: &WalkableRegions,
+    spawn: (usize, usize),
+) -> Option<Vec<(usize, usize)>> {
+    let label = regions.labels[spawn.0][spawn.1]?;
+    let size = regions.labels.len();
+    // Number of tiles to carve to get to every tile, walkable tiles costing nothing
+    let mut carved = vec![vec![usize::MAX; size]; size];
+    let mut parents = vec![vec![None; size]; size];
+    let mut queue = VecDeque::new();
+    for (row, labels) in regions.labels.iter().enumerate() {
+        for (col, &l) in labels.iter().enumerate() {
+            if l == Some(label) {
+                carved[row][col] = 0;
+                parents[row][col] = Some((row, col));
+                queue.push_back((row, col));
+            }
+        }
+    }
+    while let Some((row, col)) = queue.pop_front() {
+        for (r, c) in steps(size, row, col) {
+            if map[r][c].tile_type == TileType::Wall {
+                continue;
+            }
+            let cost = usize::from(regions.labels[r][c].is_none());
+            if carved[row][col] + cost < carved[r][c] {
+                carved[r][c] = carved[row][col] + cost;
+                parents[r][c] = Some((row, col));
+                if cost == 0 {
+                    queue.push_front((r, c));
+                } else {
+                    queue.push_back((r, c));
+                }
+            }
+        }
+    }
+
+    // The closest tile of every other region
+    let mut closest: Vec<Option<(usize, usize)>> = vec![None; regions.sizes.len()];
+    for row in 0..size {
+        for col in 0..size {
+            if let Some(l) = regions.labels[row][col].filter(|&l| l != label) {
+                if carved[row][col] != usize::MAX
+                    && closest[l].is_none_or(|(r, c)| carved[row][col] < carved[r][c])
+                {
+                    closest[l] = Some((row, col));
+                }
+            }
+        }
+    }
+    let gain = |l: usize, (row, col): (usize, usize)| {
+        regions.sizes[l] as f64 / carved[row][col].max(1) as f64
+    };
+    let (_, target) = closest
+        .iter()
+        .enumerate()
+        .filter_map(|(l, tile)| tile.map(|tile| (l, tile)))
+        .max_by(|&(a, a_tile), &(b, b_tile)| gain(a, a_tile).total_cmp(&gain(b, b_tile)))?;
+
+    let mut path = vec![target];
+    let mut current = target;
+    while let Some(parent) = parents[current.0][current.1].filter(|&p| p != current) {
+        path.push(parent);
+        current = parent;
+    }
+    Some(path)
+}
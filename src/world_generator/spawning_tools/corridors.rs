@@ -0,0 +1,79 @@
+use std::collections::VecDeque;
+
+use robotics_lib::world::tile::{Content, Tile, TileType};
+
+use crate::world_generator::spawning_tools::connectivity::steps;
+use crate::world_generator::spawning_tools::TileMat;
+
+/// Shape of the paths of a maze or a dungeon, so that the contents follow its corridors
+pub(crate) struct Corridors {
+    /// Number of paths next to every tile, 0 for the walls
+    degrees: Vec<Vec<usize>>,
+    /// Whether any path is a dead end
+    dead_ends: bool,
+}
+
+impl Corridors {
+    pub(crate) fn new(map: &[Vec<Tile>]) -> Self {
+        let size = map.len();
+        let path = |(row, col): (usize, usize)| map[row][col].tile_type != TileType::Wall;
+        let degrees = (0..size)
+            .map(|row| {
+                (0..size)
+                    .map(|col| {
+                        if !path((row, col)) {
+                            return 0;
+                        }
+                        steps(size, row, col)
+                            .into_iter()
+                            .filter(|&step| path(step))
+                            .count()
+                    })
+                    .collect()
+            })
+            .collect::<Vec<Vec<usize>>>();
+        let dead_ends = degrees.iter().flatten().any(|&degree| degree == 1);
+        Self { degrees, dead_ends }
+    }
+
+    /// Treasures go in the dead ends, if there are any, and rocks in the corridors,
+    /// away from the junctions and the dead ends
+    pub(crate) fn allows(&self, row: usize, col: usize, content: &Content) -> bool {
+        let degree = self.degrees[row][col];
+        match content {
+            Content::Coin(_) | Content::Crate(_) => !self.dead_ends || degree == 1,
+            Content::Rock(_) => degree == 2,
+            _ => true,
+        }
+    }
+}
+
+impl TileMat {
+    /// Steps along the paths from the centre of a batch to every tile within `radius`,
+    /// indexed from the top left corner of the batch, [usize::MAX] if it's farther away
+    pub(crate) fn corridor_distances(
+        &self,
+        (row, col): (usize, usize),
+        radius: usize,
+    ) -> Vec<Vec<usize>> {
+        let mut distances = vec![vec![usize::MAX; radius * 2 + 1]; radius * 2 + 1];
+        let local = |(r, c): (usize, usize)| (r + radius - row, c + radius - col);
+        distances[radius][radius] = 0;
+        let mut queue = VecDeque::from([(row, col)]);
+        while let Some((r, c)) = queue.pop_front() {
+            let (lr, lc) = local((r, c));
+            let distance = distances[lr][lc];
+            if distance == radius {
+                continue;
+            }
+            for (nr, nc) in steps(self.size, r, c) {
+                let (lr, lc) = local((nr, nc));
+                if distances[lr][lc] == usize::MAX && self.map[nr][nc].tile_type != TileType::Wall {
+                    distances[lr][lc] = distance + 1;
+                    queue.push_back((nr, nc));
+                }
+            }
+        }
+        distances
+    }
+}
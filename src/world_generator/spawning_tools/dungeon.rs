@@ -0,0 +1,242 @@
+use std::collections::VecDeque;
+
+use noise::NoiseFn;
+use rand::prelude::StdRng;
+use rand::Rng;
+use robotics_lib::world::tile::{Content, TileType};
+
+use crate::utils::constants::{DEFAULT_ROOM_ATTEMPTS, DEFAULT_ROOM_THEME_DENSITY};
+use crate::world_generator::dungeon::{
+    OxAgDungeonLayout, OxAgDungeonOptions, OxAgRoom, OxAgRoomShape, OxAgRoomTheme,
+};
+use crate::world_generator::spawning_tools::connectivity::{articulation_points, steps};
+use crate::world_generator::spawning_tools::MazeBuilder;
+use crate::world_generator::tile_type_options::OxAgTileTypeOptions;
+
+impl MazeBuilder {
+    /// Digs rooms connected by corridors instead of a maze,
+    /// returning where the rooms, the doors and the chokepoints are
+    pub(crate) fn dungeon(
+        &mut self,
+        dungeon_options: &OxAgDungeonOptions,
+        tiletype_options: &OxAgTileTypeOptions,
+        rng: &mut StdRng,
+    ) -> OxAgDungeonLayout {
+        let mut rooms = self.place_rooms(dungeon_options, rng);
+        let mut open = vec![vec![false; self.size]; self.size];
+        for room in rooms.iter() {
+            self.dig_room(&mut open, room, dungeon_options.room_shape);
+        }
+        for (a, b) in corridors(&rooms, dungeon_options.extra_corridors, rng) {
+            dig_corridor(&mut open, centre(&rooms[a]), centre(&rooms[b]), rng);
+        }
+        for (row, tiles) in open.iter().enumerate() {
+            for (col, _) in tiles.iter().enumerate().filter(|(_, &open)| open) {
+                self.set_path(col, row, tiletype_options, rng);
+                // Every room and corridor can be walked, so deep water is made shallow
+                if !self.map[row][col].tile_type.properties().walk() {
+                    self.map[row][col].tile_type = TileType::ShallowWater;
+                }
+            }
+        }
+
+        for room in rooms.iter_mut() {
+            room.theme = dungeon_options
+                .themes
+                .iter()
+                .find(|(_, probability)| rng.gen_bool(*probability))
+                .map(|(theme, _)| *theme);
+            if let Some(theme) = room.theme {
+                self.fill_room(room, theme, rng);
+            }
+        }
+
+        let inside = |row: usize, col: usize| {
+            rooms.iter().any(|room| {
+                (room.from.0..=room.to.0).contains(&row) && (room.from.1..=room.to.1).contains(&col)
+            })
+        };
+        let mut doors = vec![];
+        for room in rooms.iter() {
+            let ((top, left), (bottom, right)) = (room.from, room.to);
+            let around = (top..=bottom)
+                .flat_map(|row| [(row, left - 1), (row, right + 1)])
+                .chain((left..=right).flat_map(|col| [(top - 1, col), (bottom + 1, col)]));
+            for (row, col) in around {
+                if open[row][col] && !inside(row, col) && !doors.contains(&(row, col)) {
+                    doors.push((row, col));
+                }
+            }
+        }
+        let articulation =
+            articulation_points(&self.map, |tile| tile.tile_type.properties().walk());
+        let chokepoints = doors
+            .iter()
+            .copied()
+            .filter(|&(row, col)| articulation[row][col])
+            .collect();
+        OxAgDungeonLayout {
+            rooms,
+            doors,
+            chokepoints,
+        }
+    }
+
+    // Random rooms that don't touch each other or the border of the map
+    fn place_rooms(&self, dungeon_options: &OxAgDungeonOptions, rng: &mut StdRng) -> Vec<OxAgRoom> {
+        let count = rng.gen_range(dungeon_options.rooms.clone());
+        let max_side = (*dungeon_options.room_size.end()).min(self.size - 2);
+        let min_side = (*dungeon_options.room_size.start()).min(max_side);
+        let mut rooms: Vec<OxAgRoom> = vec![];
+        for _ in 0..count * DEFAULT_ROOM_ATTEMPTS {
+            if rooms.len() == count {
+                break;
+            }
+            let (height, width) = (
+                rng.gen_range(min_side..=max_side),
+                rng.gen_range(min_side..=max_side),
+            );
+            let from = (
+                rng.gen_range(1..=self.size - 1 - height),
+                rng.gen_range(1..=self.size - 1 - width),
+            );
+            let to = (from.0 + height - 1, from.1 + width - 1);
+            if rooms.iter().any(|room| {
+                from.0 <= room.to.0 + 1
+                    && room.from.0 <= to.0 + 1
+                    && from.1 <= room.to.1 + 1
+                    && room.from.1 <= to.1 + 1
+            }) {
+                continue;
+            }
+            rooms.push(OxAgRoom {
+                from,
+                to,
+                theme: None,
+            });
+        }
+        rooms
+    }
+
+    fn dig_room(&self, open: &mut [Vec<bool>], room: &OxAgRoom, shape: OxAgRoomShape) {
+        let tiles = (room.from.0..=room.to.0)
+            .flat_map(|row| (room.from.1..=room.to.1).map(move |col| (row, col)));
+        match shape {
+            OxAgRoomShape::Rectangular => tiles.for_each(|(row, col)| open[row][col] = true),
+            OxAgRoomShape::Cave => {
+                // A noisy ellipse, without the pieces that are cut off from its centre
+                let (centre_row, centre_col) = centre(room);
+                let half_height = (room.to.0 - room.from.0 + 1) as f64 / 2.0;
+                let half_width = (room.to.1 - room.from.1 + 1) as f64 / 2.0;
+                let in_cave = |row: usize, col: usize| {
+                    let distance = ((row as f64 - centre_row as f64) / half_height).powi(2)
+                        + ((col as f64 - centre_col as f64) / half_width).powi(2);
+                    let noise = self.perlin.get([
+                        row as f64 / self.size as f64 * 8.0,
+                        col as f64 / self.size as f64 * 8.0,
+                    ]);
+                    distance <= 1.0 + 0.5 * noise
+                };
+                let mut queue = VecDeque::from([(centre_row, centre_col)]);
+                open[centre_row][centre_col] = true;
+                while let Some((row, col)) = queue.pop_front() {
+                    for (r, c) in steps(self.size, row, col) {
+                        if (room.from.0..=room.to.0).contains(&r)
+                            && (room.from.1..=room.to.1).contains(&c)
+                            && !open[r][c]
+                            && in_cave(r, c)
+                        {
+                            open[r][c] = true;
+                            queue.push_back((r, c));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn fill_room(&mut self, room: &OxAgRoom, theme: OxAgRoomTheme, rng: &mut StdRng) {
+        for row in room.from.0..=room.to.0 {
+            for col in room.from.1..=room.to.1 {
+                let tile = &mut self.map[row][col];
+                if tile.tile_type == TileType::Wall || !rng.gen_bool(DEFAULT_ROOM_THEME_DENSITY) {
+                    continue;
+                }
+                let content = match theme {
+                    OxAgRoomTheme::Treasure if rng.gen_bool(0.5) => {
+                        Content::Coin(rng.gen_range(1..=Content::Coin(0).properties().max()))
+                    }
+                    OxAgRoomTheme::Treasure => Content::Crate(
+                        0..rng.gen_range(1..=Content::Crate(0..0).properties().max()),
+                    ),
+                    OxAgRoomTheme::Traps => Content::Fire,
+                };
+                if tile.tile_type.properties().can_hold(&content) {
+                    tile.content = content;
+                }
+            }
+        }
+    }
+}
+
+fn centre(room: &OxAgRoom) -> (usize, usize) {
+    ((room.from.0 + room.to.0) / 2, (room.from.1 + room.to.1) / 2)
+}
+
+// The minimum spanning tree of the rooms, plus some random extra corridors
+fn corridors(rooms: &[OxAgRoom], extra_corridors: f64, rng: &mut StdRng) -> Vec<(usize, usize)> {
+    let length = |a: usize, b: usize| {
+        let (a, b) = (centre(&rooms[a]), centre(&rooms[b]));
+        a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+    };
+    let mut connected = vec![false; rooms.len()];
+    let mut corridors = vec![];
+    if let Some(first) = connected.first_mut() {
+        *first = true;
+    }
+    // Prim: the shortest corridor from the connected rooms to a new one each time
+    for _ in 1..rooms.len() {
+        let shortest = (0..rooms.len())
+            .filter(|&a| connected[a])
+            .flat_map(|a| {
+                (0..rooms.len())
+                    .filter(|&b| !connected[b])
+                    .map(move |b| (a, b))
+            })
+            .min_by_key(|&(a, b)| length(a, b));
+        if let Some((a, b)) = shortest {
+            connected[b] = true;
+            corridors.push((a, b));
+        }
+    }
+    for a in 0..rooms.len() {
+        for b in a + 1..rooms.len() {
+            if !corridors.contains(&(a, b))
+                && !corridors.contains(&(b, a))
+                && rng.gen_bool(extra_corridors)
+            {
+                corridors.push((a, b));
+            }
+        }
+    }
+    corridors
+}
+
+// An L shaped corridor between two tiles
+fn dig_corridor(
+    open: &mut [Vec<bool>],
+    from: (usize, usize),
+    to: (usize, usize),
+    rng: &mut StdRng,
+) {
+    let corner = if rng.gen_bool(0.5) {
+        (from.0, to.1)
+    } else {
+        (to.0, from.1)
+    };
+    for (a, b) in [(from, corner), (corner, to)] {
+        for row in open.iter_mut().take(a.0.max(b.0) + 1).skip(a.0.min(b.0)) {
+            row[a.1.min(b.1)..=a.1.max(b.1)].fill(true);
+        }
+    }
+}
@@ -1,25 +1,48 @@
+use crate::utils::errors::OxAgError;
 use crate::world_generator::spawning_tools::circle_spawn::spawn_circle;
 use crate::world_generator::spawning_tools::F64MatData;
 use crate::world_generator::tile_type_options::OxAgTileTypeOptions;
 use rand::prelude::StdRng;
 use rand::Rng;
 use robotics_lib::world::tile::Tile;
-use robotics_lib::world::tile::TileType::{Grass, Hill, Lava};
+use robotics_lib::world::tile::TileType::Lava;
 
 impl F64MatData {
     pub(crate) fn lava_spawn(
-        &self,
-        map: &mut Vec<Vec<Tile>>,
+        &mut self,
+        map: &mut [Vec<Tile>],
         spawn_levels: &OxAgTileTypeOptions,
         rng: &mut StdRng,
-    ) {
-        for _ in 0..=rng.gen_range(spawn_levels.lava_n.clone()) {
-            let (mut row, mut col) = (rng.gen_range(0..self.size), rng.gen_range(0..self.size));
-            while ![Grass, Hill].contains(&map[row][col].tile_type) {
-                (row, col) = (rng.gen_range(0..self.size), rng.gen_range(0..self.size));
-            }
+    ) -> Result<(), OxAgError> {
+        let requested = rng.gen_range(spawn_levels.lava_n.clone());
+        for _ in 0..requested {
             let radius = rng.gen_range(spawn_levels.lava_radius.clone());
-            spawn_circle(map, rng, self.size, row, col, radius, &(None, Some(Lava)));
+            let centre = (0..spawn_levels.lava_attempts)
+                .map(|_| (rng.gen_range(0..self.size), rng.gen_range(0..self.size)))
+                .find(|&(row, col)| {
+                    spawn_levels
+                        .lava_host_tiles
+                        .contains(&map[row][col].tile_type)
+                        && self.lava_pools.iter().all(|&((r, c), rad)| {
+                            let distance = ((r as f64 - row as f64).powi(2)
+                                + (c as f64 - col as f64).powi(2))
+                            .sqrt();
+                            distance - (rad + radius) as f64
+                                >= spawn_levels.lava_min_distance as f64
+                        })
+                });
+            if let Some((row, col)) = centre {
+                spawn_circle(map, rng, self.size, row, col, radius, &(None, Some(Lava)));
+                self.lava_pools.push(((row, col), radius));
+            }
+        }
+        if self.lava_pools.len() < requested {
+            Err(OxAgError::LavaPlacementFailed(
+                requested,
+                self.lava_pools.len(),
+            ))
+        } else {
+            Ok(())
         }
     }
 }
@@ -11,8 +11,9 @@ use crate::utils::constants::{
     DEFAULT_NOISE_FREQUENCY, DEFAULT_NOISE_LACUNARITY, DEFAULT_NOISE_OCTAVES,
     DEFAULT_NOISE_PERSISTENCE,
 };
+use crate::utils::errors::OxAgError::{RiverPlacementFailed, StreetPlacementFailed};
 use crate::utils::progress_bar;
-use crate::world_generator::spawning_tools::{F64MatData, TileMat};
+use crate::world_generator::spawning_tools::{find_position, F64MatData, TileMat};
 use crate::world_generator::tile_type_options::OxAgTileTypeOptions;
 
 pub(crate) fn f64_mat(seed: u64, size: usize, with_info: bool) -> F64MatData {
@@ -53,6 +54,8 @@ pub(crate) fn f64_mat(seed: u64, size: usize, with_info: bool) -> F64MatData {
         seed,
         size,
         with_info,
+        lava_pools: vec![],
+        errors: vec![],
     }
 }
 
@@ -182,20 +185,22 @@ impl F64MatData {
             })
         });
 
-        self.lava_spawn(&mut map, spawn_levels, &mut rng);
-
-        for _ in 0..rng.gen_range(spawn_levels.river_n.clone()) {
-            let (mut row, mut col) = (rng.gen_range(0..self.size), rng.gen_range(0..self.size));
-            while ![Hill, Mountain].contains(&map[row][col].tile_type) || self.map[row][col].1 {
-                (row, col) = (rng.gen_range(0..self.size), rng.gen_range(0..self.size));
+        if let Err(error) = self.lava_spawn(&mut map, spawn_levels, &mut rng) {
+            if self.with_info {
+                println!("Lava lakes: {:?}", error);
             }
-            self.river_spawn(&mut map, row, col);
+            self.errors.push(error);
         }
+        self.volcano_spawn(&mut map, spawn_levels, &mut rng);
+        self.settlement_spawn(&mut map, spawn_levels, &mut rng);
+
         for _ in 0..rng.gen_range(spawn_levels.street_n.clone()) {
-            let (mut row, mut col) = (rng.gen_range(0..self.size), rng.gen_range(0..self.size));
-            while ![Hill, Grass].contains(&map[row][col].tile_type) || self.map[row][col].1 {
-                (row, col) = (rng.gen_range(0..self.size), rng.gen_range(0..self.size));
-            }
+            let Some((row, col)) = find_position(0..self.size, &mut rng, |row, col| {
+                [Hill, Grass].contains(&map[row][col].tile_type) && !self.map[row][col].1
+            }) else {
+                self.errors.push(StreetPlacementFailed);
+                break;
+            };
             let n = rng.gen_range(spawn_levels.street_len.clone());
             let dir = match rng.gen_range(0..4) {
                 0 => (0, 1),
@@ -204,13 +209,53 @@ impl F64MatData {
                 _ => (-1, 0),
             };
 
-            self.street_spawn(&mut map, row, col, &mut rng, dir, n);
+            self.street_spawn(
+                &mut map,
+                row,
+                col,
+                &mut rng,
+                dir,
+                dir,
+                n,
+                spawn_levels.bridge_max_len,
+            );
+        }
+        // The rivers flow under the bridges of the streets
+        for _ in 0..rng.gen_range(spawn_levels.river_n.clone()) {
+            let Some((row, col)) = find_position(0..self.size, &mut rng, |row, col| {
+                [Hill, Mountain].contains(&map[row][col].tile_type) && !self.map[row][col].1
+            }) else {
+                self.errors.push(RiverPlacementFailed);
+                break;
+            };
+            self.river_spawn(&mut map, row, col, spawn_levels.bridge_max_len);
         }
         TileMat {
             map,
             with_info: self.with_info,
             seed: self.seed,
             size: self.size,
+            spawn_exclusions: self
+                .lava_pools
+                .iter()
+                .map(|&(centre, radius)| (centre, radius + spawn_levels.lava_spawn_distance))
+                .collect(),
+            placement_report: vec![],
+            errors: self.errors,
+            heights: self
+                .map
+                .iter()
+                .map(|row| row.iter().map(|(height, _)| *height).collect())
+                .collect(),
+            priorities: vec![vec![None; self.size]; self.size],
+            corridors: None,
+            anchor_distances: vec![],
+            region_mask: vec![],
+            spawn_report: None,
+            connectivity_report: None,
+            dungeon_layout: None,
+            maze_solution: None,
+            teleport_report: None,
         }
     }
 }
@@ -1,9 +1,16 @@
 use crate::utils::constants::{
     DEFAULT_NOISE_FREQUENCY, DEFAULT_NOISE_LACUNARITY, DEFAULT_NOISE_OCTAVES,
 };
+use crate::utils::progress_bar;
+use crate::world_generator::connectivity::OxAgConnectivityOptions;
 use crate::world_generator::content_options::OxAgContentOptions;
-use crate::world_generator::spawning_tools::circle_spawn::spawn_circle;
+use crate::world_generator::dungeon::OxAgDungeonOptions;
+use crate::world_generator::maze_options::OxAgMazeAlgorithm;
+use crate::world_generator::regions::OxAgRegionOption;
+use crate::world_generator::spawn_strategy::OxAgSpawnStrategy;
+use crate::world_generator::spawning_tools::corridors::Corridors;
 use crate::world_generator::spawning_tools::{MazeBuilder, TileMat};
+use crate::world_generator::teleports::OxAgTeleportOptions;
 use crate::world_generator::tile_type_options::OxAgTileTypeOptions;
 use noise::{Fbm, MultiFractal, NoiseFn, Perlin};
 use rand::prelude::StdRng;
@@ -11,7 +18,17 @@ use rand::seq::SliceRandom;
 use rand::{Rng, SeedableRng};
 use robotics_lib::world::tile::{Content, Tile, TileType};
 
-pub(crate) fn maze_builder_init(seed: u64, size: usize) -> MazeBuilder {
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn maze_builder_init(
+    seed: u64,
+    size: usize,
+    with_info: bool,
+    height_multiplier: f64,
+    algorithm: OxAgMazeAlgorithm,
+    braid: f64,
+    loops: usize,
+    goal_percentile: f64,
+) -> MazeBuilder {
     let perlin = Fbm::<Perlin>::new(seed as u32)
         .set_octaves(DEFAULT_NOISE_OCTAVES)
         .set_frequency(DEFAULT_NOISE_FREQUENCY)
@@ -32,72 +49,128 @@ pub(crate) fn maze_builder_init(seed: u64, size: usize) -> MazeBuilder {
             size
         ],
         perlin,
+        algorithm,
+        braid,
+        loops,
+        goal_percentile,
+        with_info,
+        height_multiplier,
     }
 }
 
-pub(crate) fn random_tile(rng: &mut StdRng) -> Vec<TileType> {
-    let mut tile_type = vec![
-        TileType::Grass,
-        TileType::ShallowWater,
-        TileType::Sand,
-        TileType::Snow,
-    ];
-    tile_type.shuffle(rng);
-    tile_type
-}
-
 impl MazeBuilder {
     // maze builder
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn builder(
         mut self,
         content_option: &Vec<(Content, OxAgContentOptions)>,
+        region_options: &[OxAgRegionOption],
+        spawn_strategy: &OxAgSpawnStrategy,
+        connectivity_options: Option<&OxAgConnectivityOptions>,
+        teleport_options: Option<&OxAgTeleportOptions>,
+        dungeon_options: Option<&OxAgDungeonOptions>,
         tiletype_options: &OxAgTileTypeOptions,
-    ) -> (Vec<Vec<Tile>>, (usize, usize)) {
+    ) -> (TileMat, (usize, usize)) {
         let rng = &mut StdRng::seed_from_u64(self.seed);
         let (spawn_x, spawn_y) = self.random_point(rng);
-        self.maze_builder_loop(spawn_x as i32, spawn_y as i32, rng, tiletype_options);
+        let dungeon_layout = match dungeon_options {
+            Some(dungeon_options) => {
+                if self.with_info {
+                    println!("Digging the dungeon");
+                }
+                Some(self.dungeon(dungeon_options, tiletype_options, rng))
+            }
+            None => {
+                if self.with_info {
+                    println!("Carving the maze with {:?}", self.algorithm);
+                }
+                self.maze(spawn_x, spawn_y, tiletype_options, rng);
+                None
+            }
+        };
+        let heights = self.elevation();
 
-        self.teleport_spawner(rng);
-        self.spawn_end(rng);
+        let goal_percentile = self.goal_percentile;
 
         let tile_map = TileMat {
+            corridors: Some(Corridors::new(&self.map)),
             map: self.map,
-            with_info: false,
+            with_info: self.with_info,
             seed: self.seed,
             size: self.size,
+            spawn_exclusions: vec![],
+            placement_report: vec![],
+            errors: vec![],
+            heights,
+            priorities: vec![vec![None; self.size]; self.size],
+            anchor_distances: vec![],
+            region_mask: vec![],
+            spawn_report: None,
+            connectivity_report: None,
+            dungeon_layout,
+            maze_solution: None,
+            teleport_report: None,
         };
 
-        let result = tile_map.spawn_contents(content_option);
-        (result.0.map, result.1)
+        let (mut tile_map, spawn) = tile_map.spawn_contents(
+            content_option,
+            region_options,
+            spawn_strategy,
+            connectivity_options,
+            teleport_options,
+        );
+        // The goal comes last, since it depends on the spawn
+        tile_map.maze_solution = tile_map.place_goal(spawn, goal_percentile, rng);
+        (tile_map, spawn)
     }
 
-    fn teleport_spawner(&mut self, rng: &mut StdRng) {
-        let max = rng.gen_range(0.0..(self.size as f32 * 0.1));
-        if max < 1.0 {
-            return;
-        }
-        for _ in 0..max as usize {
-            let (x, y) = self.random_not_street(rng);
-            self.map[y][x].content = Content::JollyBlock(1);
+    // Elevation of every tile from the noise, like the open world height map,
+    // which is returned for the contents that follow the slopes
+    fn elevation(&mut self) -> Vec<Vec<f64>> {
+        let mut heights = vec![vec![0.0; self.size]; self.size];
+        let mut i = (0, self.size.pow(2));
+        for (y, row) in self.map.iter_mut().enumerate() {
+            for (x, tile) in row.iter_mut().enumerate() {
+                let (nx, ny) = (x as f64 / self.size as f64, y as f64 / self.size as f64);
+                let value = self.perlin.get([nx, ny]).clamp(-1.0, 1.0);
+                tile.elevation = ((value + 1.0) * self.height_multiplier) as usize;
+                heights[y][x] = value;
+                if self.with_info {
+                    progress_bar(i.0, i.1, "Generating height map:", 50, "■");
+                    i.0 += 1;
+                }
+            }
         }
+        heights
     }
 
-    fn random_not_street(&self, rng: &mut StdRng) -> (usize, usize) {
-        let (mut x, mut y) = (
-            rng.gen_range(1..self.size - 1),
-            rng.gen_range(1..self.size - 1),
-        );
-        while self.map[y][x].tile_type == TileType::Wall {
-            (x, y) = (
-                rng.gen_range(1..self.size - 1),
-                rng.gen_range(1..self.size - 1),
-            );
+    // Carves the maze with its algorithm, then adds the loops
+    fn maze(
+        &mut self,
+        spawn_x: usize,
+        spawn_y: usize,
+        tiletype_options: &OxAgTileTypeOptions,
+        rng: &mut StdRng,
+    ) {
+        let start = ((spawn_x - 1) / 2, (spawn_y - 1) / 2);
+        let open = match self.algorithm {
+            OxAgMazeAlgorithm::RecursiveBacktracker => None,
+            OxAgMazeAlgorithm::Prim => Some(self.prim(start, rng)),
+            OxAgMazeAlgorithm::Kruskal => Some(self.kruskal(rng)),
+            OxAgMazeAlgorithm::Wilson => Some(self.wilson(start, rng)),
+            OxAgMazeAlgorithm::Eller => Some(self.eller(rng)),
+            OxAgMazeAlgorithm::RecursiveDivision => Some(self.recursive_division(rng)),
+        };
+        match open {
+            Some(open) => self.carve(&open, tiletype_options, rng),
+            None => self.maze_builder_loop(spawn_x as i32, spawn_y as i32, rng, tiletype_options),
         }
-        (x, y)
+        self.braid(tiletype_options, rng);
+        self.add_loops(tiletype_options, rng);
     }
 
     // Path setter
-    fn set_path(
+    pub(crate) fn set_path(
         &mut self,
         x: usize,
         y: usize,
@@ -194,6 +267,97 @@ impl MazeBuilder {
             }
         }
     }
+    // Sets every open tile to path
+    fn carve(
+        &mut self,
+        open: &[Vec<bool>],
+        tiletype_options: &OxAgTileTypeOptions,
+        rng: &mut StdRng,
+    ) {
+        for (y, row) in open.iter().enumerate() {
+            for (x, _) in row.iter().enumerate().filter(|(_, &open)| open) {
+                self.set_path(x, y, tiletype_options, rng);
+            }
+        }
+    }
+
+    // Opens a wall of the given share of the dead ends, joining them to another dead end if possible
+    fn braid(&mut self, tiletype_options: &OxAgTileTypeOptions, rng: &mut StdRng) {
+        if self.braid <= 0.0 {
+            return;
+        }
+        let mut dead_ends = self
+            .cell_tiles()
+            .into_iter()
+            .filter(|&(x, y)| self.is_dead_end(x, y))
+            .collect::<Vec<(usize, usize)>>();
+        dead_ends.shuffle(rng);
+        let count = (dead_ends.len() as f64 * self.braid).round() as usize;
+        for (x, y) in dead_ends.into_iter().take(count) {
+            // It could have been joined by a previous dead end
+            if !self.is_dead_end(x, y) {
+                continue;
+            }
+            let walls = self.closed_walls(x, y);
+            let to_dead_end = walls
+                .iter()
+                .copied()
+                .filter(|&(wall_x, wall_y)| self.is_dead_end(2 * wall_x - x, 2 * wall_y - y))
+                .collect::<Vec<(usize, usize)>>();
+            if let Some(&(wall_x, wall_y)) = to_dead_end.choose(rng).or(walls.choose(rng)) {
+                self.set_path(wall_x, wall_y, tiletype_options, rng);
+            }
+        }
+    }
+
+    // Opens random walls between cells, each one adding a loop
+    fn add_loops(&mut self, tiletype_options: &OxAgTileTypeOptions, rng: &mut StdRng) {
+        if self.loops == 0 {
+            return;
+        }
+        let mut walls = self
+            .cell_tiles()
+            .into_iter()
+            .flat_map(|(x, y)| {
+                self.closed_walls(x, y)
+                    .into_iter()
+                    .filter(move |&wall| wall > (x, y))
+            })
+            .collect::<Vec<(usize, usize)>>();
+        walls.shuffle(rng);
+        for (x, y) in walls.into_iter().take(self.loops) {
+            self.set_path(x, y, tiletype_options, rng);
+        }
+    }
+
+    // Tiles of the cells of the maze, at the odd positions
+    fn cell_tiles(&self) -> Vec<(usize, usize)> {
+        (1..self.size - 1)
+            .step_by(2)
+            .flat_map(|y| (1..self.size - 1).step_by(2).map(move |x| (x, y)))
+            .collect()
+    }
+
+    // Walls between the cell and the next ones
+    fn closed_walls(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+            .into_iter()
+            .filter(|&(wall_x, wall_y)| {
+                (1..self.size - 1).contains(&wall_x)
+                    && (1..self.size - 1).contains(&wall_y)
+                    && self.map[wall_y][wall_x].tile_type == TileType::Wall
+            })
+            .collect()
+    }
+
+    fn is_dead_end(&self, x: usize, y: usize) -> bool {
+        [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+            .into_iter()
+            .filter(|&(wall_x, wall_y)| self.map[wall_y][wall_x].tile_type != TileType::Wall)
+            .count()
+            == 1
+    }
+
     // Check if where i want to place a path is wall
     fn is_wall(&self, x: usize, y: usize) -> TileType {
         if 0 < x && x < self.size && 0 < y && y < self.size {
@@ -222,27 +386,6 @@ impl MazeBuilder {
         num
     }
 
-    fn spawn_end(&mut self, rng: &mut StdRng) {
-        let (x, y) = self.random_not_street(rng);
-        self.map[y][x].content = Content::JollyBlock(1);
-    }
-
-    fn random_circles(&mut self, rng: &mut StdRng, tile: TileType) {
-        let (spawn_x, spawn_y) = self.random_point(rng);
-        let size = self.size as f32;
-        let radius = rng.gen_range(1..size.sqrt() as usize);
-        spawn_circle(
-            &mut self.map,
-            rng,
-            self.size,
-            spawn_x,
-            spawn_y,
-            radius,
-            &(None, Some(tile)),
-        );
-        self.map[spawn_x][spawn_y].tile_type = TileType::Teleport(false);
-    }
-
     fn maze_builder_loop(
         &mut self,
         start_x: i32,
@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+
+use rand::prelude::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::world_generator::spawning_tools::MazeBuilder;
+
+/// Tiles of the maze that are paths, by `[y][x]`
+type Open = Vec<Vec<bool>>;
+/// Maze cell, as `(x, y)` in cells: the cell `(x, y)` is the tile `(2x + 1, 2y + 1)`
+type Cell = (usize, usize);
+
+// The cells are at the odd positions of the map, with a wall between any two of them
+impl MazeBuilder {
+    fn cells(&self) -> usize {
+        (self.size - 1) / 2
+    }
+
+    fn closed(&self) -> Open {
+        vec![vec![false; self.size]; self.size]
+    }
+
+    fn cell_neighbours(&self, (x, y): Cell) -> Vec<Cell> {
+        let cells = self.cells();
+        let mut neighbours = vec![];
+        if x > 0 {
+            neighbours.push((x - 1, y));
+        }
+        if x + 1 < cells {
+            neighbours.push((x + 1, y));
+        }
+        if y > 0 {
+            neighbours.push((x, y - 1));
+        }
+        if y + 1 < cells {
+            neighbours.push((x, y + 1));
+        }
+        neighbours
+    }
+
+    /// Randomized Prim: grows the maze from the start, linking a random frontier cell each time
+    pub(crate) fn prim(&self, start: Cell, rng: &mut StdRng) -> Open {
+        let cells = self.cells();
+        let mut open = self.closed();
+        let mut in_maze = vec![vec![false; cells]; cells];
+        let mut in_frontier = vec![vec![false; cells]; cells];
+        in_maze[start.1][start.0] = true;
+        open_cell(&mut open, start);
+        let mut frontier = vec![];
+        for (x, y) in self.cell_neighbours(start) {
+            in_frontier[y][x] = true;
+            frontier.push((x, y));
+        }
+        while !frontier.is_empty() {
+            let cell = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+            let neighbours = self.cell_neighbours(cell);
+            let linked = neighbours
+                .iter()
+                .copied()
+                .filter(|&(x, y)| in_maze[y][x])
+                .collect::<Vec<Cell>>();
+            if let Some(&linked) = linked.choose(rng) {
+                open_passage(&mut open, cell, linked);
+            }
+            in_maze[cell.1][cell.0] = true;
+            for (x, y) in neighbours {
+                if !in_maze[y][x] && !in_frontier[y][x] {
+                    in_frontier[y][x] = true;
+                    frontier.push((x, y));
+                }
+            }
+        }
+        open
+    }
+
+    /// Randomized Kruskal: opens the walls in random order when they join two separate areas
+    pub(crate) fn kruskal(&self, rng: &mut StdRng) -> Open {
+        let cells = self.cells();
+        let mut open = self.closed();
+        let mut walls = vec![];
+        for y in 0..cells {
+            for x in 0..cells {
+                open_cell(&mut open, (x, y));
+                if x + 1 < cells {
+                    walls.push(((x, y), (x + 1, y)));
+                }
+                if y + 1 < cells {
+                    walls.push(((x, y), (x, y + 1)));
+                }
+            }
+        }
+        walls.shuffle(rng);
+
+        let mut parents = (0..cells * cells).collect::<Vec<usize>>();
+        fn root(parents: &mut [usize], mut set: usize) -> usize {
+            while parents[set] != set {
+                parents[set] = parents[parents[set]];
+                set = parents[set];
+            }
+            set
+        }
+        for (a, b) in walls {
+            let (root_a, root_b) = (
+                root(&mut parents, a.1 * cells + a.0),
+                root(&mut parents, b.1 * cells + b.0),
+            );
+            if root_a != root_b {
+                parents[root_a] = root_b;
+                open_passage(&mut open, a, b);
+            }
+        }
+        open
+    }
+
+    /// Wilson: random walks from every cell until they hit the maze, without their loops
+    pub(crate) fn wilson(&self, start: Cell, rng: &mut StdRng) -> Open {
+        let cells = self.cells();
+        let mut open = self.closed();
+        let mut in_maze = vec![vec![false; cells]; cells];
+        let mut next = vec![vec![start; cells]; cells];
+        in_maze[start.1][start.0] = true;
+        open_cell(&mut open, start);
+
+        let mut order = (0..cells)
+            .flat_map(|y| (0..cells).map(move |x| (x, y)))
+            .collect::<Vec<Cell>>();
+        order.shuffle(rng);
+        for cell in order {
+            // Overwriting the exit of every visited cell erases the loops of the walk
+            let mut current = cell;
+            while !in_maze[current.1][current.0] {
+                let step = *self.cell_neighbours(current).choose(rng).unwrap();
+                next[current.1][current.0] = step;
+                current = step;
+            }
+            let mut current = cell;
+            while !in_maze[current.1][current.0] {
+                let step = next[current.1][current.0];
+                in_maze[current.1][current.0] = true;
+                open_passage(&mut open, current, step);
+                current = step;
+            }
+        }
+        open
+    }
+
+    /// Eller: builds the maze one row at a time, keeping track of the connected sets
+    pub(crate) fn eller(&self, rng: &mut StdRng) -> Open {
+        let cells = self.cells();
+        let mut open = self.closed();
+        let mut sets: Vec<Option<usize>> = vec![None; cells];
+        let mut next_set = 0;
+        for y in 0..cells {
+            let last = y + 1 == cells;
+            for (x, set) in sets.iter_mut().enumerate() {
+                if set.is_none() {
+                    *set = Some(next_set);
+                    next_set += 1;
+                }
+                open_cell(&mut open, (x, y));
+            }
+            // Join the neighbouring sets, all of them in the last row
+            for x in 0..cells - 1 {
+                if sets[x] != sets[x + 1] && (last || rng.gen_bool(0.5)) {
+                    open_passage(&mut open, (x, y), (x + 1, y));
+                    let (kept, joined) = (sets[x], sets[x + 1]);
+                    sets.iter_mut()
+                        .filter(|set| **set == joined)
+                        .for_each(|set| *set = kept);
+                }
+            }
+            if last {
+                break;
+            }
+            // Every set goes down at least once
+            let mut by_set: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+            for (x, set) in sets.iter().enumerate() {
+                by_set.entry(set.unwrap()).or_default().push(x);
+            }
+            let mut next_row = vec![None; cells];
+            for (set, mut xs) in by_set {
+                xs.shuffle(rng);
+                let down = rng.gen_range(1..=xs.len());
+                for &x in xs.iter().take(down) {
+                    open_passage(&mut open, (x, y), (x, y + 1));
+                    next_row[x] = Some(set);
+                }
+            }
+            sets = next_row;
+        }
+        open
+    }
+
+    /// Recursive division: starts from an open map and splits it with walls,
+    /// leaving a single gap in each of them
+    pub(crate) fn recursive_division(&self, rng: &mut StdRng) -> Open {
+        let cells = self.cells();
+        let mut open = self.closed();
+        for y in 0..cells {
+            for x in 0..cells {
+                open_cell(&mut open, (x, y));
+                if x + 1 < cells {
+                    open_passage(&mut open, (x, y), (x + 1, y));
+                }
+                if y + 1 < cells {
+                    open_passage(&mut open, (x, y), (x, y + 1));
+                }
+            }
+        }
+
+        // Chambers as (x, y, width, height), in cells
+        let mut chambers = vec![(0, 0, cells, cells)];
+        while let Some((x0, y0, width, height)) = chambers.pop() {
+            let horizontal = match (width >= 2, height >= 2) {
+                (false, false) => continue,
+                (true, false) => false,
+                (false, true) => true,
+                (true, true) if width == height => rng.gen_bool(0.5),
+                (true, true) => height > width,
+            };
+            if horizontal {
+                let wall = rng.gen_range(y0..y0 + height - 1);
+                let gap = rng.gen_range(x0..x0 + width);
+                for x in (x0..x0 + width).filter(|&x| x != gap) {
+                    open[2 * wall + 2][2 * x + 1] = false;
+                }
+                chambers.push((x0, y0, width, wall - y0 + 1));
+                chambers.push((x0, wall + 1, width, y0 + height - wall - 1));
+            } else {
+                let wall = rng.gen_range(x0..x0 + width - 1);
+                let gap = rng.gen_range(y0..y0 + height);
+                for y in (y0..y0 + height).filter(|&y| y != gap) {
+                    open[2 * y + 1][2 * wall + 2] = false;
+                }
+                chambers.push((x0, y0, wall - x0 + 1, height));
+                chambers.push((wall + 1, y0, x0 + width - wall - 1, height));
+            }
+        }
+        open
+    }
+}
+
+fn open_cell(open: &mut Open, (x, y): Cell) {
+    open[2 * y + 1][2 * x + 1] = true;
+}
+
+fn open_passage(open: &mut Open, a: Cell, b: Cell) {
+    open_cell(open, a);
+    open_cell(open, b);
+    open[a.1 + b.1 + 1][a.0 + b.0 + 1] = true;
+}
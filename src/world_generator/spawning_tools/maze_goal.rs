@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+
+use rand::prelude::StdRng;
+use rand::seq::SliceRandom;
+use robotics_lib::world::tile::Content;
+
+use crate::utils::errors::OxAgError::MazeGoalPlacementFailed;
+use crate::world_generator::maze_options::OxAgMazeSolution;
+use crate::world_generator::spawning_tools::connectivity::steps;
+use crate::world_generator::spawning_tools::TileMat;
+
+impl TileMat {
+    /// Places the goal on a free walkable tile reachable from the spawn, at the given percentile
+    /// of the shortest path distances: 1 is the farthest tile, 0 the closest one.
+    /// The contents already placed are left untouched.
+    ///
+    /// Returns the goal with the shortest path to it, [None] if no free tile can be reached.
+    pub(crate) fn place_goal(
+        &mut self,
+        spawn: (usize, usize),
+        percentile: f64,
+        rng: &mut StdRng,
+    ) -> Option<OxAgMazeSolution> {
+        if !self.map[spawn.0][spawn.1].tile_type.properties().walk() {
+            self.errors.push(MazeGoalPlacementFailed);
+            return None;
+        }
+        let mut parents = vec![vec![None; self.size]; self.size];
+        let mut distances = vec![vec![usize::MAX; self.size]; self.size];
+        distances[spawn.0][spawn.1] = 0;
+        let mut reached = vec![];
+        let mut queue = VecDeque::from([spawn]);
+        while let Some((row, col)) = queue.pop_front() {
+            for (r, c) in steps(self.size, row, col) {
+                if distances[r][c] == usize::MAX && self.map[r][c].tile_type.properties().walk() {
+                    distances[r][c] = distances[row][col] + 1;
+                    parents[r][c] = Some((row, col));
+                    reached.push((r, c));
+                    queue.push_back((r, c));
+                }
+            }
+        }
+        // The tiles are reached in distance order
+        let free = reached
+            .into_iter()
+            .filter(|&(row, col)| {
+                let tile = &self.map[row][col];
+                tile.content == Content::None
+                    && tile
+                        .tile_type
+                        .properties()
+                        .can_hold(&Content::JollyBlock(1))
+            })
+            .collect::<Vec<(usize, usize)>>();
+        let Some(last) = free.len().checked_sub(1) else {
+            if self.with_info {
+                println!("No free tile for the goal can be reached from the spawn");
+            }
+            self.errors.push(MazeGoalPlacementFailed);
+            return None;
+        };
+        let (row, col) = free[(last as f64 * percentile).round() as usize];
+        let goal = *free
+            .iter()
+            .filter(|&&(r, c)| distances[r][c] == distances[row][col])
+            .copied()
+            .collect::<Vec<(usize, usize)>>()
+            .choose(rng)?;
+
+        let mut path = vec![goal];
+        let mut current = goal;
+        while let Some(parent) = parents[current.0][current.1] {
+            path.push(parent);
+            current = parent;
+        }
+        path.reverse();
+        self.map[goal.0][goal.1].content = Content::JollyBlock(1);
+        Some(OxAgMazeSolution {
+            goal,
+            length: path.len() - 1,
+            path,
+        })
+    }
+}
@@ -1,20 +1,50 @@
+use std::mem::discriminant;
+use std::ops::Range;
+
 use noise::{Fbm, Perlin};
 use rand::prelude::StdRng;
+use rand::seq::SliceRandom;
 use rand::{Rng, SeedableRng};
 use robotics_lib::world::tile::{Content, Tile, TileType};
 use robotics_lib::world::world_generator::get_tiletype_percentage;
 use strum::IntoEnumIterator;
 
-use crate::world_generator::content_options::OxAgContentOptions;
+use crate::utils::constants::DEFAULT_SPAWN_ATTEMPTS;
+use crate::utils::errors::OxAgError;
+use crate::utils::errors::OxAgError::ContentPlacementFailed;
+use crate::world_generator::connectivity::{OxAgConnectivityOptions, OxAgConnectivityReport};
+use crate::world_generator::content_options::{anchor_depth, OxAgContentOptions};
+use crate::world_generator::dungeon::OxAgDungeonLayout;
+use crate::world_generator::maze_options::{OxAgMazeAlgorithm, OxAgMazeSolution};
+use crate::world_generator::placement_report::OxAgPlacementFailure::{
+    AnchorNotFound, NoFreeTile, NoValidTile, Overwritten, SpacingTooLarge,
+};
+use crate::world_generator::placement_report::{OxAgContentPlacement, OxAgPlacementReport};
+use crate::world_generator::regions::OxAgRegionOption;
+use crate::world_generator::spawn_strategy::{OxAgSpawnReport, OxAgSpawnStrategy};
+use crate::world_generator::spawning_tools::corridors::Corridors;
+use crate::world_generator::teleports::{OxAgTeleportOptions, OxAgTeleportReport};
 
 pub(crate) mod batch_spawn;
 mod circle_spawn;
+pub(crate) mod connectivity;
+mod corridors;
+mod dungeon;
 mod lava_spawn;
 pub(crate) mod matrix_spawn;
 pub(crate) mod maze;
+mod maze_algorithms;
+mod maze_goal;
+pub(crate) mod multi_spawn;
+mod placement_rules;
+pub(crate) mod poisson_spawn;
 pub(crate) mod random_spawn;
 mod river_spawn;
+mod settlement_spawn;
+mod spawn_point;
 mod street_spawn;
+mod teleports;
+mod volcano_spawn;
 
 pub(crate) struct F64MatData {
     map: Vec<Vec<(f64, bool)>>,
@@ -23,6 +53,8 @@ pub(crate) struct F64MatData {
     seed: u64,
     size: usize,
     with_info: bool,
+    lava_pools: Vec<((usize, usize), usize)>,
+    errors: Vec<OxAgError>,
 }
 
 pub(crate) struct MazeBuilder {
@@ -30,6 +62,15 @@ pub(crate) struct MazeBuilder {
     size: usize,
     map: Vec<Vec<Tile>>,
     perlin: Fbm<Perlin>,
+    algorithm: OxAgMazeAlgorithm,
+    /// Share of the dead ends that are opened
+    braid: f64,
+    /// Number of extra walls opened between cells
+    loops: usize,
+    /// Percentile of the distance from the spawn where the goal is placed
+    goal_percentile: f64,
+    with_info: bool,
+    height_multiplier: f64,
 }
 
 pub(crate) struct TileMat {
@@ -37,23 +78,78 @@ pub(crate) struct TileMat {
     with_info: bool,
     seed: u64,
     size: usize,
+    spawn_exclusions: Vec<((usize, usize), usize)>,
+    pub(crate) placement_report: OxAgPlacementReport,
+    pub(crate) errors: Vec<OxAgError>,
+    heights: Vec<Vec<f64>>,
+    /// Priority of the content placed on every tile, if placed from the content options
+    priorities: Vec<Vec<Option<usize>>>,
+    /// Paths of the maze or dungeon, [None] in the open world
+    corridors: Option<Corridors>,
+    /// Distance of every tile from the anchor of the content being placed
+    anchor_distances: Vec<Vec<usize>>,
+    /// Where the options of the content being placed apply, everywhere if empty
+    region_mask: Vec<Vec<bool>>,
+    pub(crate) spawn_report: Option<OxAgSpawnReport>,
+    pub(crate) connectivity_report: Option<OxAgConnectivityReport>,
+    pub(crate) dungeon_layout: Option<OxAgDungeonLayout>,
+    pub(crate) maze_solution: Option<OxAgMazeSolution>,
+    pub(crate) teleport_report: Option<OxAgTeleportReport>,
 }
 
+type RegionPass<'a> = (&'a Content, &'a OxAgContentOptions, Vec<Vec<bool>>);
+
 impl TileMat {
     pub(crate) fn spawn_contents(
         mut self,
         content_options: &Vec<(Content, OxAgContentOptions)>,
+        region_options: &[OxAgRegionOption],
+        spawn_strategy: &OxAgSpawnStrategy,
+        connectivity_options: Option<&OxAgConnectivityOptions>,
+        teleport_options: Option<&OxAgTeleportOptions>,
     ) -> (Self, (usize, usize)) {
         let mut rng = StdRng::seed_from_u64(self.seed);
+        // The spawn comes first, since the regions can depend on it
+        let spawn = self.choose_spawn(&mut rng, spawn_strategy);
+        self.connect(spawn, connectivity_options);
+        // The teleports come before the contents, since their clearance changes the tiles
+        self.teleport_report = teleport_options.map(|options| self.place_teleports(spawn, options));
         let percentage_map = get_tiletype_percentage(&self.map);
+        self.fill_placed_contents(content_options, &mut rng);
         if self.with_info {
             println!("Spawning contents:")
         }
-        for (content, content_option) in content_options.iter() {
+        // Anchored contents always come after their anchors, whatever their priority.
+        // Then exact counts go last, so that no other content can overwrite them,
+        // the others from the lowest to the highest priority.
+        let mut passes = self.region_passes(content_options, region_options, spawn);
+        passes.sort_by_key(|(content, content_option, _)| {
+            (
+                anchor_depth(content_options, region_options, content),
+                content_option.exact_count.is_some(),
+                content_option.priority,
+            )
+        });
+        let mut placements = vec![];
+        for (content, content_option, region_mask) in passes {
             let content = &content.to_default();
+            let region_share = if region_mask.is_empty() {
+                1.0
+            } else {
+                region_mask
+                    .iter()
+                    .flatten()
+                    .filter(|&&inside| inside)
+                    .count() as f64
+                    / self.size.pow(2) as f64
+            };
+            self.region_mask = region_mask;
             let percentage = TileType::iter()
                 .filter_map(|tiletype| {
-                    if tiletype.properties().can_hold(content) {
+                    if tiletype.properties().can_hold(content)
+                        && (content_option.allowed_tile_types.is_empty()
+                            || content_option.allowed_tile_types.contains(&tiletype))
+                    {
                         match percentage_map.get(&tiletype) {
                             Some(percentage) => Some(percentage),
                             None => Some(&0.0),
@@ -63,25 +159,169 @@ impl TileMat {
                     }
                 })
                 .sum::<f64>()
-                * content_option.percentage;
-            if content_option.is_present && percentage > 0.0 {
-                if content_option.in_batches {
-                    self.spawn_batches(content, content_option, percentage, &mut rng);
-                } else {
-                    self.spawn_randomly(content, content_option, percentage, &mut rng);
+                * content_option.percentage
+                * region_share;
+            if content_option.exact_count.is_none()
+                && !(content_option.is_present && percentage > 0.0)
+            {
+                if self.with_info {
+                    println!("Skipping {:?}", content);
+                }
+                continue;
+            }
+
+            let mut report = OxAgContentPlacement::new(content);
+            if let Some(anchor) = &content_option.anchor {
+                self.anchor_distances = self.distances_from(&anchor.content);
+                if !self.anchor_distances.iter().flatten().any(|&d| d == 0) {
+                    if self.with_info {
+                        println!("No {:?} to anchor {:?} to", anchor.content, content);
+                    }
+                    report.requested = content_option.exact_count.unwrap_or_default();
+                    report.reasons.push(AnchorNotFound);
+                    placements.push((report, vec![]));
+                    continue;
                 }
-            } else if self.with_info {
-                println!("Skipping {:?}", content);
+            }
+            let positions = if let Some(count) = content_option.exact_count {
+                self.spawn_exactly(content, content_option, count, &mut rng, &mut report)
+            } else if content_option.in_batches {
+                self.spawn_batches(content, content_option, percentage, &mut rng, &mut report)
+            } else {
+                self.spawn_randomly(content, content_option, percentage, &mut rng, &mut report)
+            };
+            placements.push((report, positions));
+        }
+        // The passes of the regions of a content share its entry
+        let mut placement_report: OxAgPlacementReport = vec![];
+        for (report, positions) in placements {
+            let report = self.finalize_placement(report, positions);
+            match placement_report
+                .iter_mut()
+                .find(|entry| entry.content == report.content)
+            {
+                Some(entry) => entry.merge(report),
+                None => placement_report.push(report),
             }
         }
-        self.choose_spawn(&mut rng)
+        self.placement_report = placement_report;
+        for report in self.placement_report.iter() {
+            if report.reasons.iter().any(|reason| {
+                [NoValidTile, NoFreeTile, SpacingTooLarge, AnchorNotFound].contains(reason)
+            }) {
+                self.errors
+                    .push(ContentPlacementFailed(report.content.clone()));
+            }
+        }
+        let spawn = self.check_spawn(spawn, &mut rng, spawn_strategy);
+        (self, spawn)
     }
 
-    fn choose_spawn(self, rng: &mut StdRng) -> (Self, (usize, usize)) {
-        let (mut row, mut col) = (rng.gen_range(0..self.size), rng.gen_range(0..self.size));
-        while !self.map[row][col].tile_type.properties().walk() {
-            (row, col) = (rng.gen_range(0..self.size), rng.gen_range(0..self.size));
+    // Every content is spawned outside of its regions with its global options,
+    // then inside each of its regions with the options of the region.
+    // Returns the content, the options and where they apply (everywhere if empty).
+    fn region_passes<'a>(
+        &self,
+        content_options: &'a [(Content, OxAgContentOptions)],
+        region_options: &'a [OxAgRegionOption],
+        spawn: (usize, usize),
+    ) -> Vec<RegionPass<'a>> {
+        let mask = |inside: &dyn Fn(usize, usize) -> bool| {
+            (0..self.size)
+                .map(|row| (0..self.size).map(|col| inside(row, col)).collect())
+                .collect::<Vec<Vec<bool>>>()
+        };
+        let regions_of = |content: &Content| {
+            region_options
+                .iter()
+                .filter(|(_, c, _)| c.to_default() == content.to_default())
+                .collect::<Vec<&OxAgRegionOption>>()
+        };
+
+        let mut passes = vec![];
+        for (content, content_option) in content_options {
+            let regions = regions_of(content);
+            if regions.is_empty() {
+                passes.push((content, content_option, vec![]));
+                continue;
+            }
+            passes.push((
+                content,
+                content_option,
+                mask(&|row, col| {
+                    !regions
+                        .iter()
+                        .any(|(region, ..)| region.contains(&self.map, spawn, row, col))
+                }),
+            ));
+            for (region, content, content_option) in regions {
+                passes.push((
+                    content,
+                    content_option,
+                    mask(&|row, col| region.contains(&self.map, spawn, row, col)),
+                ));
+            }
+        }
+        // Regions of contents without global options
+        for (region, content, content_option) in region_options.iter().filter(|(_, content, _)| {
+            !content_options
+                .iter()
+                .any(|(c, _)| c.to_default() == content.to_default())
+        }) {
+            passes.push((
+                content,
+                content_option,
+                mask(&|row, col| region.contains(&self.map, spawn, row, col)),
+            ));
+        }
+        passes
+    }
+
+    // Counts the placed tiles that still hold the content at the end of the spawning
+    fn finalize_placement(
+        &self,
+        mut report: OxAgContentPlacement,
+        mut positions: Vec<(usize, usize)>,
+    ) -> OxAgContentPlacement {
+        positions.sort_unstable();
+        positions.dedup();
+        report.placed = positions
+            .iter()
+            .filter(|&&(row, col)| {
+                discriminant(&self.map[row][col].content) == discriminant(&report.content)
+            })
+            .count();
+        report.failed = report.requested.saturating_sub(report.placed);
+        if report.failed > 0 && report.reasons.is_empty() {
+            report.reasons.push(Overwritten);
+        }
+        report
+    }
+}
+
+/// Looks for a position in `range` on both axes that is accepted by `accept`.
+///
+/// Random positions are tried first, then every position is considered,
+/// so that it only fails when there is none.
+pub(crate) fn find_position(
+    range: Range<usize>,
+    rng: &mut StdRng,
+    accept: impl Fn(usize, usize) -> bool,
+) -> Option<(usize, usize)> {
+    if range.is_empty() {
+        return None;
+    }
+    for _ in 0..DEFAULT_SPAWN_ATTEMPTS {
+        let (row, col) = (rng.gen_range(range.clone()), rng.gen_range(range.clone()));
+        if accept(row, col) {
+            return Some((row, col));
         }
-        (self, (row, col))
     }
+    range
+        .clone()
+        .flat_map(|row| range.clone().map(move |col| (row, col)))
+        .filter(|&(row, col)| accept(row, col))
+        .collect::<Vec<(usize, usize)>>()
+        .choose(rng)
+        .copied()
 }
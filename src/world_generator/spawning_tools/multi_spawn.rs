@@ -0,0 +1,118 @@
+use rand::prelude::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use robotics_lib::world::tile::{Content, Tile};
+use std::mem::discriminant;
+
+use crate::utils::constants::DEFAULT_MULTI_SPAWN_ATTEMPTS;
+use crate::utils::errors::OxAgError;
+use crate::utils::errors::OxAgError::{NotEnoughSpawns, UnfairSpawns};
+use crate::world_generator::multi_spawn::{OxAgMultiSpawnOptions, OxAgSpawnFairness};
+use crate::world_generator::spawning_tools::connectivity::WalkableRegions;
+
+/// Chooses the spawn points of a multi-robot world, the first one being `first`.
+///
+/// The others are picked at random among the walkable tiles following the options,
+/// each tile at most once, starting over when they can't all be placed or aren't fair.
+pub(crate) fn choose_spawns(
+    map: &[Vec<Tile>],
+    first: (usize, usize),
+    options: &OxAgMultiSpawnOptions,
+    seed: u64,
+) -> Result<Vec<(usize, usize)>, OxAgError> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let regions = WalkableRegions::new(map);
+    let candidates = regions
+        .labels
+        .iter()
+        .enumerate()
+        .flat_map(|(row, labels)| labels.iter().enumerate().map(move |(col, l)| (row, col, l)))
+        .filter(|(_, _, label)| {
+            label.is_some() && (!options.same_region || **label == regions.labels[first.0][first.1])
+        })
+        .map(|(row, col, _)| (row, col))
+        .collect::<Vec<(usize, usize)>>();
+    let far_enough = |a: (usize, usize), b: (usize, usize)| {
+        ((a.0.abs_diff(b.0).pow(2) + a.1.abs_diff(b.1).pow(2)) as f64).sqrt()
+            >= options.min_distance as f64
+    };
+
+    let mut most_found = 1;
+    let mut placed_any = false;
+    for _ in 0..DEFAULT_MULTI_SPAWN_ATTEMPTS {
+        let mut spawns = vec![first];
+        // Every tile is tried once, so that no two spawns share it
+        let mut order = candidates.clone();
+        order.shuffle(&mut rng);
+        for candidate in order {
+            if spawns.len() == options.count {
+                break;
+            }
+            if spawns
+                .iter()
+                .all(|&spawn| spawn != candidate && far_enough(spawn, candidate))
+            {
+                spawns.push(candidate);
+            }
+        }
+        most_found = most_found.max(spawns.len());
+        if spawns.len() < options.count {
+            continue;
+        }
+        placed_any = true;
+        if options
+            .fairness
+            .as_ref()
+            .is_none_or(|fairness| is_fair(map, &spawns, fairness))
+        {
+            return Ok(spawns);
+        }
+    }
+    if placed_any {
+        Err(UnfairSpawns)
+    } else {
+        Err(NotEnoughSpawns(options.count, most_found))
+    }
+}
+
+// Whether the resources around the spawns differ at most by the tolerance
+fn is_fair(map: &[Vec<Tile>], spawns: &[(usize, usize)], fairness: &OxAgSpawnFairness) -> bool {
+    let counts = spawns
+        .iter()
+        .map(|&spawn| resources_around(map, spawn, fairness))
+        .collect::<Vec<usize>>();
+    let (min, max) = (
+        counts.iter().min().copied().unwrap_or_default(),
+        counts.iter().max().copied().unwrap_or_default(),
+    );
+    max - min <= fairness.tolerance
+}
+
+/// Number of tiles holding a resource within the fairness radius of the spawn
+pub(crate) fn resources_around(
+    map: &[Vec<Tile>],
+    (row, col): (usize, usize),
+    fairness: &OxAgSpawnFairness,
+) -> usize {
+    let size = map.len();
+    let radius = fairness.radius;
+    let is_resource = |content: &Content| {
+        if fairness.resources.is_empty() {
+            !matches!(content, Content::None)
+        } else {
+            fairness
+                .resources
+                .iter()
+                .any(|resource| discriminant(resource) == discriminant(content))
+        }
+    };
+    (row.saturating_sub(radius)..=(row + radius).min(size - 1))
+        .flat_map(|r| {
+            (col.saturating_sub(radius)..=(col + radius).min(size - 1)).map(move |c| (r, c))
+        })
+        .filter(|&(r, c)| {
+            r.abs_diff(row).pow(2) + c.abs_diff(col).pow(2) <= radius.pow(2)
+                && is_resource(&map[r][c].content)
+        })
+        .count()
+}
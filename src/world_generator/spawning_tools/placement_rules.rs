@@ -0,0 +1,134 @@
+use crate::world_generator::content_options::{OxAgCollisionPolicy, OxAgContentOptions};
+use crate::world_generator::spawning_tools::random_spawn::is_free;
+use crate::world_generator::spawning_tools::TileMat;
+use robotics_lib::world::tile::{Content, Tile};
+use std::collections::VecDeque;
+use std::mem::discriminant;
+
+impl TileMat {
+    /// Whether the [Content] can be placed on the tile,
+    /// following both the tile properties and the placement rules of its options.
+    pub(crate) fn can_place(
+        &self,
+        row: usize,
+        col: usize,
+        content: &Content,
+        content_option: &OxAgContentOptions,
+    ) -> bool {
+        let tile = &self.map[row][col];
+        if (!self.region_mask.is_empty() && !self.region_mask[row][col])
+            || !tile.tile_type.properties().can_hold(content)
+            || (!content_option.allowed_tile_types.is_empty()
+                && !content_option.allowed_tile_types.contains(&tile.tile_type))
+            || content_option
+                .elevation_band
+                .as_ref()
+                .is_some_and(|band| !band.contains(&self.height(row, col)))
+            || content_option
+                .anchor
+                .as_ref()
+                .is_some_and(|anchor| !anchor.distance.contains(&self.anchor_distances[row][col]))
+            || self
+                .corridors
+                .as_ref()
+                .is_some_and(|corridors| !corridors.allows(row, col, content))
+        {
+            return false;
+        }
+        if content_option.required_neighbours.is_empty()
+            && content_option.forbidden_neighbours.is_empty()
+        {
+            return true;
+        }
+
+        let radius = content_option.neighbour_radius;
+        let mut has_required = content_option.required_neighbours.is_empty();
+        for r in row.saturating_sub(radius)..=(row + radius).min(self.size - 1) {
+            for c in col.saturating_sub(radius)..=(col + radius).min(self.size - 1) {
+                if (r, c) == (row, col) {
+                    continue;
+                }
+                let tile_type = self.map[r][c].tile_type;
+                if content_option.forbidden_neighbours.contains(&tile_type) {
+                    return false;
+                }
+                has_required |= content_option.required_neighbours.contains(&tile_type);
+            }
+        }
+        has_required
+    }
+
+    /// Height of the noise map on the tile, 0 if the map has none
+    pub(crate) fn height(&self, row: usize, col: usize) -> f64 {
+        self.heights
+            .get(row)
+            .and_then(|heights| heights.get(col))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Whether the content can take the tile following its collision policy,
+    /// given what the tile already holds
+    pub(crate) fn can_overwrite(
+        &self,
+        row: usize,
+        col: usize,
+        content_option: &OxAgContentOptions,
+    ) -> bool {
+        is_free(&self.map[row][col].content)
+            || match content_option.collision_policy {
+                OxAgCollisionPolicy::NeverOverwrite => false,
+                // Contents that were not placed from the options (e.g. settlements) have priority 0
+                OxAgCollisionPolicy::OverwriteLowerPriority => {
+                    self.priorities[row][col].unwrap_or_default() < content_option.priority
+                }
+                OxAgCollisionPolicy::OverwriteAnything => true,
+            }
+    }
+
+    /// Every tile where the [Content] can be placed
+    pub(crate) fn placement_candidates(
+        &self,
+        content: &Content,
+        content_option: &OxAgContentOptions,
+    ) -> Vec<(usize, usize)> {
+        (0..self.size)
+            .flat_map(|row| (0..self.size).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.can_place(row, col, content, content_option))
+            .collect()
+    }
+
+    /// Distance (in tiles, diagonals included) of every tile from the nearest one holding
+    /// the [Content], [usize::MAX] if there is none
+    pub(crate) fn distances_from(&self, content: &Content) -> Vec<Vec<usize>> {
+        self.distances_where(|tile| discriminant(&tile.content) == discriminant(content))
+    }
+
+    /// Distance (in tiles, diagonals included) of every tile from the nearest one
+    /// matching `source`, [usize::MAX] if there is none
+    pub(crate) fn distances_where(&self, source: impl Fn(&Tile) -> bool) -> Vec<Vec<usize>> {
+        let mut distances = vec![vec![usize::MAX; self.size]; self.size];
+        let mut queue = VecDeque::new();
+        for (row, rows) in self.map.iter().enumerate() {
+            for (col, tile) in rows.iter().enumerate() {
+                if source(tile) {
+                    distances[row][col] = 0;
+                    queue.push_back((row, col));
+                }
+            }
+        }
+        while let Some((row, col)) = queue.pop_front() {
+            let next = distances[row][col] + 1;
+            let around = (row.saturating_sub(1)..=(row + 1).min(self.size - 1)).flat_map(|r| {
+                (col.saturating_sub(1)..=(col + 1).min(self.size - 1)).map(move |c| (r, c))
+            });
+            for (r, c) in around {
+                if distances[r][c] > next {
+                    distances[r][c] = next;
+                    queue.push_back((r, c));
+                }
+            }
+        }
+        distances
+    }
+}
@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+
+use rand::prelude::StdRng;
+use rand::seq::SliceRandom;
+
+/// Picks up to `count` positions among the `candidates`, keeping every pair
+/// at least `spacing` tiles apart (Poisson-disk / blue-noise sampling).
+///
+/// The candidates are visited in random order and a position is accepted only if no
+/// accepted one falls within `spacing`, so the result is evenly spread over the map.
+/// The `existing` positions, e.g. placed by an earlier pass, are never returned
+/// but the candidates keep the spacing from them too.
+pub(crate) fn poisson_disk(
+    mut candidates: Vec<(usize, usize)>,
+    existing: &[(usize, usize)],
+    spacing: usize,
+    count: usize,
+    rng: &mut StdRng,
+) -> Vec<(usize, usize)> {
+    candidates.shuffle(rng);
+    // Accepted positions bucketed in cells as wide as the spacing:
+    // only the surrounding cells have to be checked for each candidate
+    let mut grid: HashMap<(usize, usize), Vec<(usize, usize)>> = HashMap::new();
+    for &(row, col) in existing {
+        grid.entry((row / spacing, col / spacing))
+            .or_default()
+            .push((row, col));
+    }
+    let mut accepted = vec![];
+    for (row, col) in candidates {
+        if accepted.len() == count {
+            break;
+        }
+        let cell = (row / spacing, col / spacing);
+        let too_close = (cell.0.saturating_sub(1)..=cell.0 + 1).any(|cell_row| {
+            (cell.1.saturating_sub(1)..=cell.1 + 1).any(|cell_col| {
+                grid.get(&(cell_row, cell_col)).is_some_and(|points| {
+                    points.iter().any(|&(r, c)| {
+                        (r.abs_diff(row).pow(2) + c.abs_diff(col).pow(2)) < spacing.pow(2)
+                    })
+                })
+            })
+        });
+        if !too_close {
+            grid.entry(cell).or_default().push((row, col));
+            accepted.push((row, col));
+        }
+    }
+    accepted
+}
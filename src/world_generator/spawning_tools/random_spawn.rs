@@ -1,8 +1,15 @@
 use std::cmp::max;
+use std::mem::discriminant;
 
 use crate::world_generator::content_options::OxAgContentOptions;
+use crate::world_generator::placement_report::OxAgContentPlacement;
+use crate::world_generator::placement_report::OxAgPlacementFailure::{
+    NoFreeTile, NoValidTile, SpacingTooLarge,
+};
+use crate::world_generator::spawning_tools::poisson_spawn::poisson_disk;
 use crate::world_generator::spawning_tools::TileMat;
 use rand::prelude::StdRng;
+use rand::seq::SliceRandom;
 use rand::Rng;
 use robotics_lib::world::tile::Content;
 
@@ -13,7 +20,8 @@ impl TileMat {
         content_option: &OxAgContentOptions,
         percentage: f64,
         rng: &mut StdRng,
-    ) {
+        report: &mut OxAgContentPlacement,
+    ) -> Vec<(usize, usize)> {
         let max_spawn_number = if content_option.with_max_spawn_number {
             content_option.max_spawn_number
         } else {
@@ -23,16 +31,67 @@ impl TileMat {
             );
             rng.gen_range(content_option.min_spawn_number..=max)
         };
-        for _ in 0..max_spawn_number {
-            let (mut row, mut col) = (rng.gen_range(0..self.size), rng.gen_range(0..self.size));
-            while !self.map[row][col].tile_type.properties().can_hold(content) {
-                (row, col) = (rng.gen_range(0..self.size), rng.gen_range(0..self.size))
+        report.requested = max_spawn_number;
+        let candidates = self.placement_candidates(content, content_option);
+        if candidates.is_empty() {
+            if self.with_info {
+                println!("No tile satisfies the placement rules of {:?}", content);
             }
-            let mut value = 0;
-            if content.properties().max() != 0 {
-                value = rng.gen_range(0..content.properties().max());
+            report.reasons.push(NoValidTile);
+            return vec![];
+        }
+        let candidates = candidates
+            .into_iter()
+            .filter(|&(row, col)| self.can_overwrite(row, col, content_option))
+            .collect::<Vec<(usize, usize)>>();
+        if candidates.is_empty() {
+            if self.with_info {
+                println!("Every valid tile for {:?} is already taken", content);
+            }
+            report.reasons.push(NoFreeTile);
+            return vec![];
+        }
+        let positions = if content_option.min_spacing > 0 {
+            let positions = poisson_disk(
+                candidates,
+                &self.positions_of(content),
+                content_option.min_spacing,
+                max_spawn_number,
+                rng,
+            );
+            if positions.len() < max_spawn_number {
+                if self.with_info {
+                    println!(
+                        "Only {} of {} {:?} fit with a spacing of {}",
+                        positions.len(),
+                        max_spawn_number,
+                        content,
+                        content_option.min_spacing
+                    );
+                }
+                report.reasons.push(SpacingTooLarge);
             }
-            self.map[row][col].content = content.to_value(value);
+            positions
+        } else {
+            if candidates.len() < max_spawn_number {
+                if self.with_info {
+                    println!(
+                        "Only {} of {} {:?} have a free tile",
+                        candidates.len(),
+                        max_spawn_number,
+                        content
+                    );
+                }
+                report.reasons.push(NoFreeTile);
+            }
+            candidates
+                .choose_multiple(rng, max_spawn_number)
+                .copied()
+                .collect()
+        };
+        for &(row, col) in positions.iter() {
+            self.map[row][col].content = content_option.random_value(content, rng, None);
+            self.priorities[row][col] = Some(content_option.priority);
             //if self.with_info {
             //    progress_bar(
             //        i,
@@ -43,5 +102,99 @@ impl TileMat {
             //    );
             //}
         }
+        positions
     }
+
+    /// Places the [Content] so that exactly `count` tiles hold it,
+    /// counting the ones already present and without overwriting any other [Content].
+    pub(crate) fn spawn_exactly(
+        &mut self,
+        content: &Content,
+        content_option: &OxAgContentOptions,
+        count: usize,
+        rng: &mut StdRng,
+        report: &mut OxAgContentPlacement,
+    ) -> Vec<(usize, usize)> {
+        report.requested = count;
+        let mut positions = (0..self.size)
+            .flat_map(|row| (0..self.size).map(move |col| (row, col)))
+            .filter(|&(row, col)| {
+                discriminant(&self.map[row][col].content) == discriminant(content)
+                    && (self.region_mask.is_empty() || self.region_mask[row][col])
+            })
+            .collect::<Vec<(usize, usize)>>();
+        if positions.len() > count {
+            // The extras, e.g. placed by a settlement, are taken away
+            positions.shuffle(rng);
+            for (row, col) in positions.drain(count..) {
+                self.map[row][col].content = Content::None;
+                self.priorities[row][col] = None;
+            }
+            return positions;
+        }
+        let missing = count.saturating_sub(positions.len());
+        if missing == 0 {
+            return positions;
+        }
+
+        let candidates = self.placement_candidates(content, content_option);
+        let free = candidates
+            .iter()
+            .copied()
+            .filter(|&(row, col)| is_free(&self.map[row][col].content))
+            .collect::<Vec<(usize, usize)>>();
+        let chosen = if content_option.min_spacing > 0 {
+            poisson_disk(
+                free.clone(),
+                &self.positions_of(content),
+                content_option.min_spacing,
+                missing,
+                rng,
+            )
+        } else {
+            let mut free = free.clone();
+            free.shuffle(rng);
+            free.truncate(missing);
+            free
+        };
+
+        if chosen.len() < missing {
+            report.reasons.push(if candidates.is_empty() {
+                NoValidTile
+            } else if free.len() < missing {
+                NoFreeTile
+            } else {
+                SpacingTooLarge
+            });
+            if self.with_info {
+                println!(
+                    "Only {} of {} {:?} could be placed",
+                    positions.len() + chosen.len(),
+                    count,
+                    content
+                );
+            }
+        }
+        for &(row, col) in chosen.iter() {
+            self.map[row][col].content = content_option.random_value(content, rng, None);
+            self.priorities[row][col] = Some(content_option.priority);
+        }
+        positions.extend(chosen);
+        positions
+    }
+
+    /// The tiles of the whole map already holding the [Content], whatever its value
+    fn positions_of(&self, content: &Content) -> Vec<(usize, usize)> {
+        (0..self.size)
+            .flat_map(|row| (0..self.size).map(move |col| (row, col)))
+            .filter(|&(row, col)| {
+                discriminant(&self.map[row][col].content) == discriminant(content)
+            })
+            .collect()
+    }
+}
+
+/// Whether a tile can receive a [Content] without overwriting another one
+pub(crate) fn is_free(content: &Content) -> bool {
+    matches!(content, Content::None | Content::Water(_))
 }
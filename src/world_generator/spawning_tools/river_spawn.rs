@@ -1,32 +1,22 @@
 use crate::world_generator::spawning_tools::F64MatData;
 use robotics_lib::world::tile::Tile;
-use robotics_lib::world::tile::TileType::{DeepWater, Lava, ShallowWater};
-use std::collections::VecDeque;
+use robotics_lib::world::tile::TileType::{DeepWater, Lava, ShallowWater, Street};
 
 impl F64MatData {
-    pub(crate) fn river_spawn(&mut self, map: &mut Vec<Vec<Tile>>, row: usize, col: usize) -> bool {
-        let mut directions = [(2.0, (-1, 0)), (2.0, (0, 1)), (2.0, (1, 0)), (2.0, (0, -1))];
+    pub(crate) fn river_spawn(
+        &mut self,
+        map: &mut Vec<Vec<Tile>>,
+        row: usize,
+        col: usize,
+        max_bridge: usize,
+    ) -> bool {
+        let mut directions = [(-1, 0), (0, 1), (1, 0), (0, -1)]
+            .iter()
+            .filter_map(|dir| self.river_target(map, row, col, *dir, max_bridge))
+            .map(|(new_row, new_col)| (self.map[new_row][new_col].0, (new_row, new_col)))
+            .collect::<Vec<(f64, (usize, usize))>>();
 
-        if row as i32 > 0 && !self.map[row - 1][col].1 && !(map[row - 1][col].tile_type == Lava) {
-            directions[0].0 = self.map[row - 1][col].0;
-        }
-        if col + 1 < self.size
-            && !self.map[row][col + 1].1
-            && !(map[row][col + 1].tile_type == Lava)
-        {
-            directions[1].0 = self.map[row][col + 1].0;
-        }
-        if row + 1 < self.size
-            && !self.map[row + 1][col].1
-            && !(map[row + 1][col].tile_type == Lava)
-        {
-            directions[2].0 = self.map[row + 1][col].0;
-        }
-        if col as i32 > 0 && !self.map[row][col - 1].1 && !(map[row][col - 1].tile_type == Lava) {
-            directions[3].0 = self.map[row][col - 1].0;
-        }
-
-        if directions.iter().all(|(v, _)| *v == 2.0) {
+        if directions.is_empty() {
             return false;
         }
 
@@ -39,21 +29,49 @@ impl F64MatData {
 
         directions.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
 
-        let mut directions = VecDeque::from(directions);
-
-        while !directions.is_empty() {
-            let (value, (row_offset, col_offset)) = directions.pop_front().unwrap();
-            if value == 2.0 {
-                return false;
-            }
-            let (new_row, new_col) = (
-                (row as i32 + row_offset) as usize,
-                (col as i32 + col_offset) as usize,
-            );
-            if self.river_spawn(map, new_row, new_col) {
+        for (_, (new_row, new_col)) in directions {
+            if self.river_spawn(map, new_row, new_col, max_bridge) {
                 return true;
             }
         }
         false
     }
+
+    // Returns the next tile of the river in the given direction.
+    // Existing streets are bridges: the river flows under them in a straight line
+    // as long as the crossing is not longer than `max_bridge`.
+    fn river_target(
+        &self,
+        map: &[Vec<Tile>],
+        row: usize,
+        col: usize,
+        dir: (isize, isize),
+        max_bridge: usize,
+    ) -> Option<(usize, usize)> {
+        let (mut new_row, mut new_col) = (row as isize + dir.0, col as isize + dir.1);
+        let mut span = 0;
+        loop {
+            if new_row < 0
+                || new_col < 0
+                || new_row >= self.size as isize
+                || new_col >= self.size as isize
+            {
+                return None;
+            }
+            if map[new_row as usize][new_col as usize].tile_type != Street {
+                break;
+            }
+            span += 1;
+            if span > max_bridge {
+                return None;
+            }
+            (new_row, new_col) = (new_row + dir.0, new_col + dir.1);
+        }
+        let (new_row, new_col) = (new_row as usize, new_col as usize);
+        if self.map[new_row][new_col].1 || map[new_row][new_col].tile_type == Lava {
+            None
+        } else {
+            Some((new_row, new_col))
+        }
+    }
 }
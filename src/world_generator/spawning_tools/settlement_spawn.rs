@@ -0,0 +1,178 @@
+use crate::utils::constants::{
+    DEFAULT_SETTLEMENT_ATTEMPTS, DEFAULT_SETTLEMENT_BLOCK_SIZE, DEFAULT_SETTLEMENT_FLAT_RATIO,
+    DEFAULT_SETTLEMENT_WATER_DISTANCE,
+};
+use crate::world_generator::content_options::OxAgContentOptions;
+use crate::world_generator::spawning_tools::{F64MatData, TileMat};
+use crate::world_generator::tile_type_options::OxAgTileTypeOptions;
+use rand::prelude::StdRng;
+use rand::Rng;
+use robotics_lib::world::tile::TileType::{DeepWater, Grass, Sand, ShallowWater, Street};
+use robotics_lib::world::tile::{Content, Tile};
+
+impl F64MatData {
+    pub(crate) fn settlement_spawn(
+        &mut self,
+        map: &mut [Vec<Tile>],
+        spawn_levels: &OxAgTileTypeOptions,
+        rng: &mut StdRng,
+    ) {
+        for _ in 0..rng.gen_range(spawn_levels.settlement_n.clone()) {
+            let radius = rng.gen_range(spawn_levels.settlement_radius.clone());
+            if radius == 0 {
+                continue;
+            }
+            if let Some((row, col)) = self.settlement_centre(map, radius, rng) {
+                self.build_settlement(map, row, col, radius, spawn_levels.settlement_density, rng);
+            } else if self.with_info {
+                println!("No flat area near water found for a settlement");
+            }
+        }
+    }
+
+    // Looks for a flat Grass/Sand area with water nearby
+    fn settlement_centre(
+        &self,
+        map: &[Vec<Tile>],
+        radius: usize,
+        rng: &mut StdRng,
+    ) -> Option<(usize, usize)> {
+        if self.size <= radius * 2 {
+            return None;
+        }
+        for _ in 0..DEFAULT_SETTLEMENT_ATTEMPTS {
+            let (row, col) = (
+                rng.gen_range(radius..self.size - radius),
+                rng.gen_range(radius..self.size - radius),
+            );
+            if ![Grass, Sand].contains(&map[row][col].tile_type) || self.map[row][col].1 {
+                continue;
+            }
+
+            let area = (row - radius..=row + radius)
+                .flat_map(|r| (col - radius..=col + radius).map(move |c| (r, c)));
+            let (mut flat, mut total) = (0, 0);
+            for (r, c) in area {
+                total += 1;
+                if [Grass, Sand].contains(&map[r][c].tile_type) && !self.map[r][c].1 {
+                    flat += 1;
+                }
+            }
+            if (flat as f64 / total as f64) < DEFAULT_SETTLEMENT_FLAT_RATIO {
+                continue;
+            }
+
+            let reach = radius + DEFAULT_SETTLEMENT_WATER_DISTANCE;
+            let near_water =
+                (row.saturating_sub(reach)..=(row + reach).min(self.size - 1)).any(|r| {
+                    (col.saturating_sub(reach)..=(col + reach).min(self.size - 1))
+                        .any(|c| [ShallowWater, DeepWater].contains(&map[r][c].tile_type))
+                });
+            if near_water {
+                return Some((row, col));
+            }
+        }
+        None
+    }
+
+    // Lays out the street grid, then the town centre and the buildings along the streets
+    fn build_settlement(
+        &mut self,
+        map: &mut [Vec<Tile>],
+        row: usize,
+        col: usize,
+        radius: usize,
+        density: f64,
+        rng: &mut StdRng,
+    ) {
+        let block = DEFAULT_SETTLEMENT_BLOCK_SIZE as isize;
+        let on_line = |v: usize, centre: usize| (v as isize - centre as isize) % block == 0;
+        let rows = row - radius..=row + radius;
+        let cols = col - radius..=col + radius;
+
+        // Street grid with a bin on every intersection
+        for r in rows.clone() {
+            for c in cols.clone() {
+                if ![Grass, Sand].contains(&map[r][c].tile_type) || self.map[r][c].1 {
+                    continue;
+                }
+                if on_line(r, row) || on_line(c, col) {
+                    map[r][c].tile_type = Street;
+                    map[r][c].content = Content::None;
+                    if on_line(r, row) && on_line(c, col) {
+                        place(map, rng, r, c, Content::Bin(0..0));
+                    }
+                    self.map[r][c].1 = true;
+                }
+            }
+        }
+
+        // Market and bank on the blocks next to the central intersection,
+        // before the buildings so that they keep their lots
+        for ((r, c), content) in [
+            ((row + 1, col + 1), Content::Market(0)),
+            ((row - 1, col - 1), Content::Bank(0..0)),
+        ] {
+            if [Grass, Sand].contains(&map[r][c].tile_type) && !self.map[r][c].1 {
+                place(map, rng, r, c, content);
+                self.map[r][c].1 = true;
+            }
+        }
+
+        // Buildings on the lots facing a street
+        for r in rows.clone() {
+            for c in cols.clone() {
+                if ![Grass, Sand].contains(&map[r][c].tile_type) || self.map[r][c].1 {
+                    continue;
+                }
+                let faces_street =
+                    [(-1, 0), (0, 1), (1, 0), (0, -1)].iter().any(|&(dr, dc)| {
+                        match (r.checked_add_signed(dr), c.checked_add_signed(dc)) {
+                            (Some(nr), Some(nc)) => {
+                                rows.contains(&nr)
+                                    && cols.contains(&nc)
+                                    && map[nr][nc].tile_type == Street
+                            }
+                            _ => false,
+                        }
+                    });
+                if faces_street && rng.gen_bool(density) {
+                    place(map, rng, r, c, Content::Building);
+                    self.map[r][c].1 = true;
+                }
+            }
+        }
+    }
+}
+
+impl TileMat {
+    /// Gives the contents placed before the content options, like the bins, banks and markets
+    /// of the settlements, the capacity of their options
+    pub(crate) fn fill_placed_contents(
+        &mut self,
+        content_options: &[(Content, OxAgContentOptions)],
+        rng: &mut StdRng,
+    ) {
+        for (content, content_option) in content_options
+            .iter()
+            .filter(|(_, content_option)| content_option.capacity.is_some())
+        {
+            let content = content.to_default();
+            for tile in self.map.iter_mut().flatten() {
+                if tile.content.to_default() == content {
+                    tile.content = content_option.random_value(&content, rng, None);
+                }
+            }
+        }
+    }
+}
+
+fn place(map: &mut [Vec<Tile>], rng: &mut StdRng, row: usize, col: usize, content: Content) {
+    if map[row][col].tile_type.properties().can_hold(&content) {
+        let mut value = 0;
+        if content.properties().max() != 0 {
+            value = rng.gen_range(0..content.properties().max());
+        }
+        map[row][col].content = content.to_value(value);
+    }
+}
@@ -0,0 +1,276 @@
+use rand::prelude::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use robotics_lib::world::tile::{Content, Tile, TileType};
+use std::cmp::Reverse;
+use std::mem::discriminant;
+
+use crate::utils::constants::DEFAULT_SPAWN_ATTEMPTS;
+use crate::utils::errors::OxAgError::{SpawnPlacementFailed, SpawnStrategyNotSatisfied};
+use crate::world_generator::spawn_strategy::{OxAgSpawnReport, OxAgSpawnStrategy};
+use crate::world_generator::spawning_tools::connectivity::{reachable, WalkableRegions};
+use crate::world_generator::spawning_tools::{find_position, TileMat};
+
+impl TileMat {
+    /// Chooses the spawn following the strategy, before the contents are placed
+    pub(crate) fn choose_spawn(
+        &mut self,
+        rng: &mut StdRng,
+        strategy: &OxAgSpawnStrategy,
+    ) -> (usize, usize) {
+        let walkable = |row: usize, col: usize| self.map[row][col].tile_type.properties().walk();
+        let chosen = match strategy {
+            OxAgSpawnStrategy::Random | OxAgSpawnStrategy::Resources { .. } => {
+                self.random_spawn(rng)
+            }
+            OxAgSpawnStrategy::CentreMost => {
+                let centre = (self.size - 1) as f64 / 2.0;
+                let distance = |&(row, col): &(usize, usize)| {
+                    (row as f64 - centre).powi(2) + (col as f64 - centre).powi(2)
+                };
+                self.tiles()
+                    .filter(|&(row, col)| walkable(row, col))
+                    .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            }
+            OxAgSpawnStrategy::LargestRegion => {
+                let regions = WalkableRegions::new(&self.map);
+                regions.largest().and_then(|largest| {
+                    let inside = self
+                        .tiles()
+                        .filter(|&(row, col)| regions.labels[row][col] == Some(largest))
+                        .collect::<Vec<(usize, usize)>>();
+                    let away = inside
+                        .iter()
+                        .copied()
+                        .filter(|&(row, col)| self.outside_exclusions(row, col))
+                        .collect::<Vec<(usize, usize)>>();
+                    away.choose(rng).or(inside.choose(rng)).copied()
+                })
+            }
+            OxAgSpawnStrategy::AwayFrom { .. } => self.away_from(rng, strategy),
+            OxAgSpawnStrategy::Fixed((row, col)) => {
+                let (row, col) = ((*row).min(self.size - 1), (*col).min(self.size - 1));
+                self.tiles()
+                    .filter(|&(r, c)| walkable(r, c))
+                    .min_by_key(|&(r, c)| r.abs_diff(row).pow(2) + c.abs_diff(col).pow(2))
+            }
+        };
+        let satisfied = match (strategy, chosen) {
+            (OxAgSpawnStrategy::Fixed(fixed), Some(spawn)) => *fixed == spawn,
+            (_, chosen) => chosen.is_some(),
+        };
+
+        let spawn = match chosen.or_else(|| self.random_spawn(rng)) {
+            Some(spawn) => spawn,
+            None => {
+                if self.with_info {
+                    println!("No walkable tile for the spawn, one is made walkable");
+                }
+                self.errors.push(SpawnPlacementFailed);
+                self.make_spawn(rng)
+            }
+        };
+        self.spawn_report = Some(OxAgSpawnReport {
+            strategy: strategy.clone(),
+            spawn,
+            satisfied,
+            moved_after_contents: false,
+            region_size: WalkableRegions::new(&self.map).size_of(spawn.0, spawn.1),
+            reachable_resources: None,
+        });
+        spawn
+    }
+
+    /// Checks the strategies that depend on the contents once they are placed,
+    /// moving the spawn if it no longer follows them
+    pub(crate) fn check_spawn(
+        &mut self,
+        spawn: (usize, usize),
+        rng: &mut StdRng,
+        strategy: &OxAgSpawnStrategy,
+    ) -> (usize, usize) {
+        let mut report = self.spawn_report.take().unwrap_or(OxAgSpawnReport {
+            strategy: strategy.clone(),
+            spawn,
+            satisfied: false,
+            moved_after_contents: false,
+            region_size: 0,
+            reachable_resources: None,
+        });
+        match strategy {
+            OxAgSpawnStrategy::AwayFrom {
+                contents,
+                min_distance,
+                ..
+            } if !contents.is_empty() => {
+                let distances = self.hazard_distances(strategy);
+                if distances[spawn.0][spawn.1] < *min_distance {
+                    if let Some(moved) = self.away_from(rng, strategy) {
+                        report.spawn = moved;
+                        report.moved_after_contents = true;
+                        report.satisfied = true;
+                    } else {
+                        report.satisfied = false;
+                    }
+                }
+            }
+            OxAgSpawnStrategy::Resources {
+                content,
+                count,
+                max_steps,
+            } => {
+                let resources =
+                    |spawn: (usize, usize)| count_resources(&self.map, spawn, content, *max_steps);
+                let mut best = (resources(spawn), spawn);
+                if best.0 < *count {
+                    // Every walkable tile is a candidate. The resources in the square of side
+                    // 2 * max_steps + 1 around a tile bound the ones it can reach, so the walks
+                    // go from the highest bound down and stop once no bound can do better.
+                    let bound = square_counts(&self.map, content, *max_steps);
+                    let mut candidates = self
+                        .tiles()
+                        .filter(|&(row, col)| self.map[row][col].tile_type.properties().walk())
+                        .collect::<Vec<(usize, usize)>>();
+                    candidates.sort_by_key(|&(row, col)| Reverse(bound[row][col]));
+                    for candidate in candidates {
+                        if bound[candidate.0][candidate.1] <= best.0 {
+                            break;
+                        }
+                        let found = resources(candidate);
+                        if found > best.0 {
+                            best = (found, candidate);
+                            if found >= *count {
+                                break;
+                            }
+                        }
+                    }
+                }
+                report.moved_after_contents = best.1 != spawn;
+                report.satisfied = best.0 >= *count;
+                report.spawn = best.1;
+                report.reachable_resources = Some(best.0);
+            }
+            _ => {}
+        }
+        report.region_size =
+            WalkableRegions::new(&self.map).size_of(report.spawn.0, report.spawn.1);
+        if !report.satisfied
+            && !self
+                .errors
+                .iter()
+                .any(|error| matches!(error, SpawnPlacementFailed))
+        {
+            if self.with_info {
+                println!("The spawn doesn't follow {:?}", strategy);
+            }
+            self.errors.push(SpawnStrategyNotSatisfied);
+        }
+        let spawn = report.spawn;
+        self.spawn_report = Some(report);
+        spawn
+    }
+
+    // Turns a random tile into Grass for the spawn, keeping the walls of mazes and dungeons
+    // whenever there is anything else
+    fn make_spawn(&mut self, rng: &mut StdRng) -> (usize, usize) {
+        let (row, col) = find_position(0..self.size, rng, |row, col| {
+            self.map[row][col].tile_type != TileType::Wall
+        })
+        .unwrap_or((self.size / 2, self.size / 2));
+        self.map[row][col].tile_type = TileType::Grass;
+        self.map[row][col].content = Content::None;
+        (row, col)
+    }
+
+    // A random walkable tile, trying to keep it out of the exclusion zones first
+    fn random_spawn(&self, rng: &mut StdRng) -> Option<(usize, usize)> {
+        for _ in 0..DEFAULT_SPAWN_ATTEMPTS {
+            let (row, col) = (rng.gen_range(0..self.size), rng.gen_range(0..self.size));
+            if self.map[row][col].tile_type.properties().walk() && self.outside_exclusions(row, col)
+            {
+                return Some((row, col));
+            }
+        }
+        find_position(0..self.size, rng, |row, col| {
+            self.map[row][col].tile_type.properties().walk()
+        })
+    }
+
+    fn outside_exclusions(&self, row: usize, col: usize) -> bool {
+        self.spawn_exclusions.iter().all(|&((r, c), radius)| {
+            (r as f64 - row as f64).powi(2) + (c as f64 - col as f64).powi(2)
+                > (radius as f64).powi(2)
+        })
+    }
+
+    // A random walkable tile far enough from the hazards of the strategy
+    fn away_from(&self, rng: &mut StdRng, strategy: &OxAgSpawnStrategy) -> Option<(usize, usize)> {
+        let OxAgSpawnStrategy::AwayFrom { min_distance, .. } = strategy else {
+            return None;
+        };
+        let distances = self.hazard_distances(strategy);
+        find_position(0..self.size, rng, |row, col| {
+            self.map[row][col].tile_type.properties().walk() && distances[row][col] >= *min_distance
+        })
+    }
+
+    fn hazard_distances(&self, strategy: &OxAgSpawnStrategy) -> Vec<Vec<usize>> {
+        let OxAgSpawnStrategy::AwayFrom {
+            tile_types,
+            contents,
+            ..
+        } = strategy
+        else {
+            return vec![vec![usize::MAX; self.size]; self.size];
+        };
+        self.distances_where(|tile| {
+            tile_types.contains(&tile.tile_type)
+                || contents
+                    .iter()
+                    .any(|content| discriminant(content) == discriminant(&tile.content))
+        })
+    }
+
+    fn tiles(&self) -> impl Iterator<Item = (usize, usize)> {
+        let size = self.size;
+        (0..size).flat_map(move |row| (0..size).map(move |col| (row, col)))
+    }
+}
+
+// Number of tiles holding the content in the square of radius `radius` around every tile,
+// from the prefix sums of the map
+fn square_counts(map: &[Vec<Tile>], content: &Content, radius: usize) -> Vec<Vec<usize>> {
+    let size = map.len();
+    let mut sums = vec![vec![0; size + 1]; size + 1];
+    for row in 0..size {
+        for col in 0..size {
+            let holds = discriminant(&map[row][col].content) == discriminant(content);
+            sums[row + 1][col + 1] =
+                sums[row][col + 1] + sums[row + 1][col] - sums[row][col] + holds as usize;
+        }
+    }
+    (0..size)
+        .map(|row| {
+            let (top, bottom) = (row.saturating_sub(radius), (row + radius + 1).min(size));
+            (0..size)
+                .map(|col| {
+                    let (left, right) = (col.saturating_sub(radius), (col + radius + 1).min(size));
+                    sums[bottom][right] + sums[top][left] - sums[top][right] - sums[bottom][left]
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Number of tiles holding the content within `max_steps` from the spawn
+fn count_resources(
+    map: &[Vec<Tile>],
+    spawn: (usize, usize),
+    content: &Content,
+    max_steps: usize,
+) -> usize {
+    reachable(map, spawn, max_steps)
+        .into_iter()
+        .filter(|&((row, col), _)| discriminant(&map[row][col].content) == discriminant(content))
+        .count()
+}
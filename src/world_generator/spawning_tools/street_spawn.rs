@@ -2,88 +2,122 @@ use crate::utils::constants::SAME_DIR_PROBABILITY;
 use crate::world_generator::spawning_tools::F64MatData;
 use rand::rngs::StdRng;
 use rand::Rng;
-use robotics_lib::world::tile::TileType::Street;
+use robotics_lib::world::tile::TileType::{DeepWater, ShallowWater, Street};
 use robotics_lib::world::tile::{Content, Tile};
 
+type Bridge = Vec<(usize, usize)>;
+
 impl F64MatData {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn street_spawn(
         &mut self,
         map: &mut Vec<Vec<Tile>>,
         row: usize,
         col: usize,
         rng: &mut StdRng,
+        heading: (isize, isize),
         prev_dir: (isize, isize),
         distance: usize,
+        max_bridge: usize,
     ) {
         if distance as isize - 1 < 0 || self.map[row][col].1 {
             return;
         }
 
-        self.map[row][col].1 = true;
-        map[row][col].tile_type = Street;
-        map[row][col].content = Content::None;
+        self.lay_street(map, row, col);
 
-        let directions = [(-1, 0), (0, 1), (1, 0), (0, -1)];
-        directions
-            .to_vec()
-            .retain(|&e| e != (-prev_dir.0, -prev_dir.1) && e != prev_dir);
+        let directions = [(-1, 0), (0, 1), (1, 0), (0, -1)]
+            .into_iter()
+            .filter(|&e| e != (-prev_dir.0, -prev_dir.1) && e != prev_dir)
+            .collect::<Vec<(isize, isize)>>();
 
-        let new_row = row as isize + prev_dir.0;
-        let new_col = col as isize + prev_dir.1;
-        if rng.gen_bool(SAME_DIR_PROBABILITY)
-            && 0 <= new_row
-            && 0 <= new_col
-            && new_row < self.size as isize
-            && new_col < self.size as isize
-            && !self.map[new_row as usize][new_col as usize].1
-        {
-            self.street_spawn(
-                map,
-                new_row as usize,
-                new_col as usize,
-                rng,
-                prev_dir,
-                distance - 1,
-            );
-            return;
+        // A street that can't go straight, like in front of water that can't be bridged,
+        // keeps its heading and turns back to it as soon as the obstacle has been routed around.
+        // Otherwise it keeps going straight or turns at random, taking the turn as its new heading
+        let blocked = self
+            .street_target(map, row, col, prev_dir, max_bridge, distance - 1)
+            .is_none();
+        let mut candidates = vec![];
+        if heading != prev_dir && heading != (-prev_dir.0, -prev_dir.1) {
+            candidates.push((1.0, heading));
         }
-
-        let new_row = row as isize + directions[0].0;
-        let new_col = col as isize + directions[0].1;
-        if rng.gen_bool(0.5)
-            && 0 <= new_row
-            && 0 <= new_col
-            && new_row < self.size as isize
-            && new_col < self.size as isize
-            && !self.map[new_row as usize][new_col as usize].1
-        {
-            self.street_spawn(
-                map,
-                new_row as usize,
-                new_col as usize,
-                rng,
-                prev_dir,
-                distance - 1,
-            );
-            return;
+        candidates.extend([
+            (SAME_DIR_PROBABILITY, prev_dir),
+            (0.5, directions[0]),
+            (1.0, directions[1]),
+        ]);
+        for (probability, dir) in candidates {
+            if !rng.gen_bool(probability) {
+                continue;
+            }
+            if let Some(((new_row, new_col), bridge)) =
+                self.street_target(map, row, col, dir, max_bridge, distance - 1)
+            {
+                bridge.iter().for_each(|&(bridge_row, bridge_col)| {
+                    self.lay_street(map, bridge_row, bridge_col)
+                });
+                let heading = if blocked || dir == heading {
+                    heading
+                } else {
+                    dir
+                };
+                self.street_spawn(
+                    map,
+                    new_row,
+                    new_col,
+                    rng,
+                    heading,
+                    dir,
+                    distance - 1 - bridge.len(),
+                    max_bridge,
+                );
+                return;
+            }
         }
+    }
 
-        let new_row = row as isize + directions[1].0;
-        let new_col = col as isize + directions[1].1;
-        if 0 <= new_row
-            && 0 <= new_col
-            && new_row < self.size as isize
-            && new_col < self.size as isize
-            && !self.map[new_row as usize][new_col as usize].1
-        {
-            self.street_spawn(
-                map,
-                new_row as usize,
-                new_col as usize,
-                rng,
-                prev_dir,
-                distance - 1,
-            );
+    fn lay_street(&mut self, map: &mut [Vec<Tile>], row: usize, col: usize) {
+        self.map[row][col].1 = true;
+        map[row][col].tile_type = Street;
+        map[row][col].content = Content::None;
+    }
+
+    // Returns the next land tile of the street in the given direction,
+    // together with the water tiles that have to be bridged to reach it.
+    fn street_target(
+        &self,
+        map: &[Vec<Tile>],
+        row: usize,
+        col: usize,
+        dir: (isize, isize),
+        max_bridge: usize,
+        remaining: usize,
+    ) -> Option<((usize, usize), Bridge)> {
+        let (mut new_row, mut new_col) = (row as isize + dir.0, col as isize + dir.1);
+        let mut bridge = vec![];
+        loop {
+            if new_row < 0
+                || new_col < 0
+                || new_row >= self.size as isize
+                || new_col >= self.size as isize
+            {
+                return None;
+            }
+            let (r, c) = (new_row as usize, new_col as usize);
+            if ![ShallowWater, DeepWater].contains(&map[r][c].tile_type) {
+                break;
+            }
+            bridge.push((r, c));
+            if bridge.len() > max_bridge || bridge.len() >= remaining {
+                return None;
+            }
+            (new_row, new_col) = (new_row + dir.0, new_col + dir.1);
+        }
+        let (new_row, new_col) = (new_row as usize, new_col as usize);
+        if self.map[new_row][new_col].1 {
+            None
+        } else {
+            Some(((new_row, new_col), bridge))
         }
     }
 }
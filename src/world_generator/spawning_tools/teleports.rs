@@ -0,0 +1,153 @@
+use rand::prelude::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use robotics_lib::world::tile::{Content, TileType};
+
+use crate::utils::errors::OxAgError::NotEnoughTeleports;
+use crate::world_generator::spawning_tools::connectivity::{reachable, WalkableRegions};
+use crate::world_generator::spawning_tools::TileMat;
+use crate::world_generator::teleports::{
+    OxAgTeleportOptions, OxAgTeleportPair, OxAgTeleportReport,
+};
+
+impl TileMat {
+    /// Places the pairs of teleports, every teleport in its own sector of the map
+    /// and, when possible, in a walkable region without other teleports
+    pub(crate) fn place_teleports(
+        &mut self,
+        spawn: (usize, usize),
+        options: &OxAgTeleportOptions,
+    ) -> OxAgTeleportReport {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let wanted = options.pairs * 2;
+        // A square grid of sectors, with at least a sector for every teleport
+        let sides = (wanted as f64).sqrt().ceil() as usize;
+        let size = self.size;
+        let sector = |(row, col): (usize, usize)| (row * sides / size, col * sides / size);
+
+        let regions = WalkableRegions::new(&self.map);
+        let mut candidates = (0..size)
+            .flat_map(|row| (0..size).map(move |col| (row, col)))
+            .filter(|&(row, col)| {
+                let tile = &self.map[row][col];
+                (row, col) != spawn
+                    && tile.tile_type.properties().walk()
+                    && !matches!(tile.tile_type, TileType::Teleport(_))
+                    && tile.content == Content::None
+            })
+            .collect::<Vec<(usize, usize)>>();
+        candidates.shuffle(&mut rng);
+        let mut sectors = (0..sides)
+            .flat_map(|row| (0..sides).map(move |col| (row, col)))
+            .collect::<Vec<(usize, usize)>>();
+        sectors.shuffle(&mut rng);
+
+        let mut teleports: Vec<(usize, usize)> = vec![];
+        let mut linked: Vec<usize> = vec![];
+        for current in sectors {
+            if teleports.len() == wanted {
+                break;
+            }
+            let fits = |&&(row, col): &&(usize, usize)| {
+                sector((row, col)) == current
+                    && teleports
+                        .iter()
+                        .all(|&(r, c)| r.abs_diff(row) + c.abs_diff(col) >= options.min_distance)
+            };
+            let unlinked = |&&(row, col): &&(usize, usize)| {
+                regions.labels[row][col].is_some_and(|label| !linked.contains(&label))
+            };
+            let choice = candidates
+                .iter()
+                .filter(fits)
+                .find(unlinked)
+                .or_else(|| candidates.iter().find(fits));
+            if let Some(&(row, col)) = choice {
+                teleports.push((row, col));
+                if let Some(label) = regions.labels[row][col] {
+                    linked.push(label);
+                }
+            }
+        }
+        // A teleport without its pair is left out
+        teleports.truncate(teleports.len() / 2 * 2);
+        if teleports.len() < wanted {
+            if self.with_info {
+                println!(
+                    "Only {} pairs of teleports out of {} could be placed",
+                    teleports.len() / 2,
+                    options.pairs
+                );
+            }
+            self.errors
+                .push(NotEnoughTeleports(options.pairs, teleports.len() / 2));
+        }
+
+        let mut report = OxAgTeleportReport::default();
+        for &(row, col) in teleports.iter() {
+            self.map[row][col].tile_type = TileType::Teleport(false);
+            // The walls are left untouched, so that mazes keep their corridors
+            let around = (row.saturating_sub(options.clearance)
+                ..=(row + options.clearance).min(size - 1))
+                .flat_map(|r| {
+                    (col.saturating_sub(options.clearance)
+                        ..=(col + options.clearance).min(size - 1))
+                        .map(move |c| (r, c))
+                });
+            for (r, c) in around {
+                let tile = &mut self.map[r][c];
+                if !tile.tile_type.properties().walk() && tile.tile_type != TileType::Wall {
+                    tile.tile_type = TileType::Grass;
+                    tile.content = Content::None;
+                    report.cleared += 1;
+                }
+            }
+        }
+
+        let regions = WalkableRegions::new(&self.map);
+        let label = |(row, col): (usize, usize)| regions.labels[row][col];
+        for ends in teleports.chunks(2) {
+            let (from, to) = (ends[0], ends[1]);
+            let walking_distance = if label(from) == label(to) {
+                reachable(&self.map, from, usize::MAX)
+                    .into_iter()
+                    .find(|&(tile, _)| tile == to)
+                    .map(|(_, distance)| distance)
+            } else {
+                None
+            };
+            report.pairs.push(OxAgTeleportPair {
+                ends: [from, to],
+                walking_distance,
+            });
+        }
+        let mut linked = teleports
+            .iter()
+            .filter_map(|&teleport| label(teleport))
+            .collect::<Vec<usize>>();
+        linked.sort_unstable();
+        linked.dedup();
+        report.linked_regions = linked.len();
+
+        // The regions reachable from the spawn, jumping through the pairs
+        let mut reached = label(spawn).into_iter().collect::<Vec<usize>>();
+        let mut grown = true;
+        while grown {
+            grown = false;
+            for pair in report.pairs.iter() {
+                let [from, to] = pair.ends.map(label);
+                for (a, b) in [(from, to), (to, from)] {
+                    if let (Some(a), Some(b)) = (a, b) {
+                        if reached.contains(&a) && !reached.contains(&b) {
+                            reached.push(b);
+                            grown = true;
+                        }
+                    }
+                }
+            }
+        }
+        report.reachable_walking = regions.size_of(spawn.0, spawn.1);
+        report.reachable = reached.iter().map(|&label| regions.sizes[label]).sum();
+        report
+    }
+}
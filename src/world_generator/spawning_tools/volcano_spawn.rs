@@ -0,0 +1,161 @@
+use crate::utils::constants::{DEFAULT_VOLCANO_DISTANCE, DEFAULT_VOLCANO_FIRE_PROBABILITY};
+use crate::world_generator::shapes::OxAgShape;
+use crate::world_generator::spawning_tools::circle_spawn::spawn_circle;
+use crate::world_generator::spawning_tools::F64MatData;
+use crate::world_generator::tile_type_options::OxAgTileTypeOptions;
+use rand::prelude::StdRng;
+use rand::Rng;
+use robotics_lib::world::tile::TileType::{Grass, Hill, Lava, Mountain, Sand, Snow};
+use robotics_lib::world::tile::{Content, Tile};
+
+impl F64MatData {
+    pub(crate) fn volcano_spawn(
+        &mut self,
+        map: &mut [Vec<Tile>],
+        spawn_levels: &OxAgTileTypeOptions,
+        rng: &mut StdRng,
+    ) {
+        let volcano_n = rng.gen_range(spawn_levels.volcano_n.clone());
+        if volcano_n == 0 {
+            return;
+        }
+
+        for (row, col) in self.volcano_peaks(map, volcano_n) {
+            let radius = rng.gen_range(spawn_levels.volcano_crater_radius.clone());
+            spawn_circle(map, rng, self.size, row, col, radius, &(None, Some(Lava)));
+            let crater = OxAgShape::Circle {
+                centre: (row, col),
+                radius,
+                filled: true,
+            };
+            for (r, c) in crater.cells(self.size) {
+                if map[r][c].tile_type == Lava {
+                    self.map[r][c].1 = true;
+                }
+            }
+
+            for _ in 0..rng.gen_range(spawn_levels.volcano_flows.clone()) {
+                let angle = rng.gen_range(0.0..std::f64::consts::TAU);
+                let start = (
+                    (row as f64 + radius as f64 * angle.sin())
+                        .round()
+                        .clamp(0.0, (self.size - 1) as f64) as usize,
+                    (col as f64 + radius as f64 * angle.cos())
+                        .round()
+                        .clamp(0.0, (self.size - 1) as f64) as usize,
+                );
+                let length = rng.gen_range(spawn_levels.volcano_flow_len.clone());
+                let flow = self.lava_flow(map, start, length);
+                self.burn_around(map, &flow, rng);
+            }
+        }
+    }
+
+    // Mountain (and snowy mountain) tiles that are local maxima of the height map,
+    // highest first and far apart
+    fn volcano_peaks(&self, map: &[Vec<Tile>], volcano_n: usize) -> Vec<(usize, usize)> {
+        let mut peaks = vec![];
+        for (row, rows) in map.iter().enumerate() {
+            for (col, tile) in rows.iter().enumerate() {
+                if ![Mountain, Snow].contains(&tile.tile_type) || self.map[row][col].1 {
+                    continue;
+                }
+                let height = self.map[row][col].0;
+                let is_peak = self
+                    .neighbours(row, col, true)
+                    .iter()
+                    .all(|&(r, c)| self.map[r][c].0 <= height);
+                if is_peak {
+                    peaks.push((height, (row, col)));
+                }
+            }
+        }
+        peaks.sort_unstable_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+
+        let mut chosen: Vec<(usize, usize)> = vec![];
+        for (_, (row, col)) in peaks {
+            if chosen.len() == volcano_n {
+                break;
+            }
+            if chosen
+                .iter()
+                .all(|&(r, c)| r.abs_diff(row).max(c.abs_diff(col)) >= DEFAULT_VOLCANO_DISTANCE)
+            {
+                chosen.push((row, col));
+            }
+        }
+        chosen
+    }
+
+    // Follows the steepest descent of the height map, like a river, and stops at the water
+    fn lava_flow(
+        &mut self,
+        map: &mut [Vec<Tile>],
+        start: (usize, usize),
+        length: usize,
+    ) -> Vec<(usize, usize)> {
+        let mut flow = vec![];
+        let (mut row, mut col) = start;
+        for _ in 0..length {
+            if ![Grass, Hill, Mountain, Sand, Snow, Lava].contains(&map[row][col].tile_type) {
+                break;
+            }
+            map[row][col].tile_type = Lava;
+            map[row][col].content = Content::None;
+            self.map[row][col].1 = true;
+            flow.push((row, col));
+
+            let next = self
+                .neighbours(row, col, false)
+                .into_iter()
+                .filter(|&(r, c)| map[r][c].tile_type != Lava)
+                .min_by(|a, b| {
+                    self.map[a.0][a.1]
+                        .0
+                        .partial_cmp(&self.map[b.0][b.1].0)
+                        .unwrap()
+                });
+            match next {
+                Some((r, c)) if self.map[r][c].0 <= self.map[row][col].0 => (row, col) = (r, c),
+                _ => break,
+            }
+        }
+        flow
+    }
+
+    // Surrounds the lava flow with fire and burned ground, which stays walkable
+    fn burn_around(&mut self, map: &mut [Vec<Tile>], flow: &[(usize, usize)], rng: &mut StdRng) {
+        for &(row, col) in flow {
+            for (r, c) in self.neighbours(row, col, true) {
+                if ![Grass, Hill, Sand, Snow].contains(&map[r][c].tile_type) {
+                    continue;
+                }
+                if map[r][c].tile_type.properties().can_hold(&Content::Fire)
+                    && rng.gen_bool(DEFAULT_VOLCANO_FIRE_PROBABILITY)
+                {
+                    map[r][c].content = Content::Fire;
+                } else {
+                    map[r][c].tile_type = Sand;
+                    map[r][c].content = Content::None;
+                }
+                self.map[r][c].1 = true;
+            }
+        }
+    }
+
+    fn neighbours(&self, row: usize, col: usize, diagonals: bool) -> Vec<(usize, usize)> {
+        let mut out = vec![];
+        for dr in -1..=1_isize {
+            for dc in -1..=1_isize {
+                if (dr == 0 && dc == 0) || (!diagonals && dr != 0 && dc != 0) {
+                    continue;
+                }
+                let (r, c) = (row as isize + dr, col as isize + dc);
+                if r >= 0 && c >= 0 && r < self.size as isize && c < self.size as isize {
+                    out.push((r as usize, c as usize));
+                }
+            }
+        }
+        out
+    }
+}
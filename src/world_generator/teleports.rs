@@ -0,0 +1,62 @@
+//! Options of the teleports, pairs of `Teleport` tiles spread over the map,
+//! and the report of the walkable regions they link.
+
+use crate::utils::errors::OxAgError;
+use crate::utils::errors::OxAgError::InvalidTeleportOptions;
+use crate::utils::traits::Validator;
+
+/// Options of the teleports: pairs of `Teleport` tiles, each one in its own sector of the map,
+/// so robots can cross large worlds
+#[derive(Debug, Clone, PartialEq)]
+pub struct OxAgTeleportOptions {
+    /// Number of pairs of teleports
+    pub pairs: usize,
+    /// Minimum distance, in steps, between any two teleports
+    pub min_distance: usize,
+    /// Radius of the square around every teleport whose tiles are made walkable, walls excluded
+    pub clearance: usize,
+}
+
+impl Default for OxAgTeleportOptions {
+    fn default() -> Self {
+        Self {
+            pairs: 2,
+            min_distance: 10,
+            clearance: 1,
+        }
+    }
+}
+
+impl Validator for OxAgTeleportOptions {
+    fn validate(&self) -> Result<(), OxAgError> {
+        if self.pairs == 0 {
+            return Err(InvalidTeleportOptions);
+        }
+        Ok(())
+    }
+}
+
+/// A pair of teleports of the last generated world
+#[derive(Debug, Clone, PartialEq)]
+pub struct OxAgTeleportPair {
+    /// The two teleports, as `(row, col)`
+    pub ends: [(usize, usize); 2],
+    /// Steps between the two teleports by walking,
+    /// [None] if the pair is the only link between their regions
+    pub walking_distance: Option<usize>,
+}
+
+/// Teleports of the last generated world and the walkable regions they link
+#[derive(Debug, Clone, Default)]
+pub struct OxAgTeleportReport {
+    /// Every pair of teleports
+    pub pairs: Vec<OxAgTeleportPair>,
+    /// Number of walkable regions holding at least a teleport
+    pub linked_regions: usize,
+    /// Number of walkable tiles reachable from the spawn, walking and through the pairs
+    pub reachable: usize,
+    /// Number of walkable tiles reachable from the spawn by walking only
+    pub reachable_walking: usize,
+    /// Number of tiles around the teleports that were made walkable
+    pub cleared: usize,
+}
@@ -6,6 +6,8 @@ use crate::utils::traits::{Container, FromSeed, Validator};
 use rand::prelude::StdRng;
 use rand::Rng;
 use rand::SeedableRng;
+use robotics_lib::world::tile::TileType;
+use robotics_lib::world::tile::TileType::{Grass, Hill};
 
 #[derive(Debug, Clone)]
 /// Options that determine how the tile [Tile] are spawned
@@ -35,6 +37,30 @@ pub struct OxAgTileTypeOptions {
     pub lava_n: RangeInclusive<usize>,
     /// The radius of the Lava lake
     pub lava_radius: RangeInclusive<usize>,
+    /// The [TileType] a Lava lake can be placed on
+    pub lava_host_tiles: Vec<TileType>,
+    /// The minimum distance between the shores of two Lava lakes
+    pub lava_min_distance: usize,
+    /// The minimum distance between the shore of a Lava lake and the spawn point
+    pub lava_spawn_distance: usize,
+    /// The number of random positions tried for each Lava lake before giving up on it
+    pub lava_attempts: usize,
+    /// The number of settlements you want to spawn
+    pub settlement_n: RangeInclusive<usize>,
+    /// The radius of a settlement, measured from its central intersection
+    pub settlement_radius: RangeInclusive<usize>,
+    /// The probability that a lot facing a Street [Tile] of a settlement holds a Building
+    pub settlement_density: f64,
+    /// The maximum number of water [Tile] a Street can bridge, longer crossings are routed around
+    pub bridge_max_len: usize,
+    /// The number of volcanoes you want to spawn on the Mountain peaks
+    pub volcano_n: RangeInclusive<usize>,
+    /// The number of Lava flows running down from every volcano
+    pub volcano_flows: RangeInclusive<usize>,
+    /// The length of the Lava flows running down from a volcano
+    pub volcano_flow_len: RangeInclusive<usize>,
+    /// The radius of the Lava crater of a volcano
+    pub volcano_crater_radius: RangeInclusive<usize>,
 }
 
 impl FromSeed for OxAgTileTypeOptions {
@@ -57,6 +83,18 @@ impl FromSeed for OxAgTileTypeOptions {
         let lr_max = rng.gen_range(0..lr_max);
         let st_max = rng.gen_range(1..20);
         let st_max = rng.gen_range(0..st_max);
+        let se_max = rng.gen_range(1..4);
+        let se_max = rng.gen_range(0..se_max);
+        let sr_max = rng.gen_range(4..16);
+        let sr_min = rng.gen_range(1..sr_max);
+        let se_density = rng.gen_range(0.3..=0.9);
+        let br_max = rng.gen_range(0..8).min(st_max);
+        let vo_max = rng.gen_range(0..3);
+        let vf_max = rng.gen_range(1..32);
+        let vf_min = rng.gen_range(0..vf_max);
+        let vc_max = rng.gen_range(0..4);
+        let ld_min = rng.gen_range(0..8);
+        let ls_min = rng.gen_range(0..16);
 
         Self {
             deep_water_level: -1.0..=dw_end,
@@ -71,6 +109,18 @@ impl FromSeed for OxAgTileTypeOptions {
             street_len: 0..=st_max,
             lava_n: 0..=l_max,
             lava_radius: 0..=lr_max,
+            lava_host_tiles: vec![Grass, Hill],
+            lava_min_distance: ld_min,
+            lava_spawn_distance: ls_min,
+            lava_attempts: DEFAULT_LAVA_ATTEMPTS,
+            settlement_n: 0..=se_max,
+            settlement_radius: sr_min..=sr_max,
+            settlement_density: se_density,
+            bridge_max_len: br_max,
+            volcano_n: 0..=vo_max,
+            volcano_flows: DEFAULT_VOLCANO_FLOWS,
+            volcano_flow_len: vf_min..=vf_max,
+            volcano_crater_radius: 0..=vc_max,
         }
     }
 }
@@ -107,6 +157,40 @@ impl Validator for OxAgTileTypeOptions {
             Err(OxAgError::RangesAreOutOfBounds)?
         }
 
+        // settlement density is a probability
+        if !(0.0..=1.0).contains(&self.settlement_density) {
+            Err(OxAgError::InvalidSettlementDensity)?
+        }
+
+        // a settlement needs room around its central intersection
+        if self.settlement_radius.is_empty()
+            || (*self.settlement_n.end() > 0 && *self.settlement_radius.start() == 0)
+        {
+            Err(OxAgError::InvalidSettlementRadius)?
+        }
+
+        // the lava lakes need somewhere to go and some tries to get there
+        if self.lava_host_tiles.is_empty()
+            || self.lava_attempts == 0
+            || self.lava_n.is_empty()
+            || self.lava_radius.is_empty()
+        {
+            Err(OxAgError::InvalidLavaOptions)?
+        }
+
+        // a bridge is part of a street
+        if self.bridge_max_len > *self.street_len.end() {
+            Err(OxAgError::InvalidBridgeLength)?
+        }
+
+        if self.volcano_n.is_empty()
+            || self.volcano_flows.is_empty()
+            || self.volcano_flow_len.is_empty()
+            || self.volcano_crater_radius.is_empty()
+        {
+            Err(OxAgError::InvalidVolcanoOptions)?
+        }
+
         Ok(())
     }
 }
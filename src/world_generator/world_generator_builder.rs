@@ -5,17 +5,27 @@ use robotics_lib::world::tile::Content;
 
 use crate::utils::constants::DEFAULT_SCORE;
 use crate::utils::errors::OxAgError;
-use crate::utils::errors::OxAgError::ContentOptionsNotSet;
+use crate::utils::errors::OxAgError::{
+    ContentOptionsNotSet, IncompatibleSpawnStrategy, InvalidDungeonOptions, InvalidMazeBraid,
+    InvalidMazeGoalPercentile, InvalidRegion,
+};
 use crate::utils::traits::Loadable;
 use crate::utils::traits::{FromSeed, Validator};
 use crate::utils::{generate_random_seed, generate_random_world_size, multiplier_from_seed};
+use crate::world_generator::connectivity::OxAgConnectivityOptions;
+use crate::world_generator::dungeon::OxAgDungeonOptions;
+use crate::world_generator::maze_metrics::OxAgMazeDifficulty;
+use crate::world_generator::maze_options::OxAgMazeAlgorithm;
 use crate::world_generator::presets::content_presets::OxAgContentPresets;
 use crate::world_generator::presets::environmental_presets::OxAgEnvironmentalConditionPresets;
 use crate::world_generator::presets::tile_type_presets::OxAgTileTypePresets;
+use crate::world_generator::regions::{OxAgRegion, OxAgRegionOption};
+use crate::world_generator::spawn_strategy::OxAgSpawnStrategy;
+use crate::world_generator::teleports::OxAgTeleportOptions;
 use crate::world_generator::tile_type_options::OxAgTileTypeOptions;
 use crate::world_generator::OxAgWorldGenerator;
 
-use super::content_options::OxAgContentOptions;
+use super::content_options::{check_anchor_loops, OxAgContentOptions};
 
 /// World generator builder that can be used to obtain a [OxAgWorldGenerator].
 ///
@@ -70,6 +80,21 @@ pub struct OxAgWorldGeneratorBuilder {
     /// If [None] it will be calculated via the seed.
     pub(crate) content_options: Option<Vec<(Content, OxAgContentOptions)>>,
 
+    /// [OxAgContentOptions] that take the place of the global ones inside a region.
+    ///
+    /// Empty by default.
+    pub(crate) region_options: Vec<OxAgRegionOption>,
+
+    /// [OxAgSpawnStrategy] used to choose the robot spawn.
+    ///
+    /// [Random](OxAgSpawnStrategy::Random) by default.
+    pub(crate) spawn_strategy: OxAgSpawnStrategy,
+
+    /// Optional [OxAgConnectivityOptions] used to repair the map.
+    ///
+    /// If [None] the map is not repaired.
+    pub(crate) connectivity_options: Option<OxAgConnectivityOptions>,
+
     /// Optional [OxAgEnvironmentalConditions] that will be used in the generated world.
     ///
     /// If [None] they will be calculated via the seed.
@@ -87,6 +112,41 @@ pub struct OxAgWorldGeneratorBuilder {
 
     pub(crate) maze: Option<bool>,
 
+    /// [OxAgMazeAlgorithm] used to carve the maze.
+    ///
+    /// [RecursiveBacktracker](OxAgMazeAlgorithm::RecursiveBacktracker) by default.
+    pub(crate) maze_algorithm: OxAgMazeAlgorithm,
+
+    /// Share of the dead ends of the maze that are opened, between 0 and 1.
+    ///
+    /// 0 by default, which gives a perfect maze.
+    pub(crate) maze_braid: f64,
+
+    /// Number of extra loops added to the maze.
+    ///
+    /// 0 by default.
+    pub(crate) maze_loops: usize,
+
+    /// Percentile of the distance from the spawn where the goal of the maze is placed.
+    ///
+    /// 1 by default, the farthest tile.
+    pub(crate) maze_goal_percentile: f64,
+
+    /// Optional [OxAgMazeDifficulty], if set the maze is regenerated until it has that difficulty.
+    ///
+    /// If [None] the maze is kept as generated.
+    pub(crate) maze_difficulty: Option<OxAgMazeDifficulty>,
+
+    /// Optional [OxAgDungeonOptions], if set the world is a dungeon of rooms and corridors.
+    ///
+    /// If [None] the world is not a dungeon.
+    pub(crate) dungeon_options: Option<OxAgDungeonOptions>,
+
+    /// Optional [OxAgTeleportOptions], if set pairs of teleports are placed in the world.
+    ///
+    /// If [None] the world has no teleports.
+    pub(crate) teleport_options: Option<OxAgTeleportOptions>,
+
     /// Optional [bool] that is used to log the actions to console
     pub(crate) with_info: Option<bool>,
 
@@ -119,9 +179,24 @@ impl OxAgWorldGeneratorBuilder {
         let seed = self.seed.unwrap_or(generate_random_seed());
         let size = self.size.unwrap_or(generate_random_world_size(seed));
 
-        if size < 5 && self.maze.unwrap_or(false) {
+        if size < 5 && (self.maze.unwrap_or(false) || self.dungeon_options.is_some()) {
             return Err(OxAgError::MazeSizeTooSmall);
         }
+        if self.maze.unwrap_or(false) && self.dungeon_options.is_some() {
+            return Err(InvalidDungeonOptions);
+        }
+        // These strategies can move the spawn after the regions around it, the repair
+        // and the teleports were done
+        if self.spawn_strategy.depends_on_contents()
+            && (self.connectivity_options.is_some()
+                || self.teleport_options.is_some()
+                || self
+                    .region_options
+                    .iter()
+                    .any(|(region, ..)| matches!(region, OxAgRegion::DistanceFromSpawn(_))))
+        {
+            return Err(IncompatibleSpawnStrategy);
+        }
 
         Ok(OxAgWorldGenerator {
             size,
@@ -134,6 +209,9 @@ impl OxAgWorldGeneratorBuilder {
                 .content_options
                 .clone()
                 .unwrap_or(OxAgContentOptions::new_from_seed(seed, size)),
+            region_options: self.region_options.clone(),
+            spawn_strategy: self.spawn_strategy.clone(),
+            connectivity_options: self.connectivity_options.clone(),
             environmental_conditions: self
                 .environmental_conditions
                 .clone()
@@ -142,8 +220,22 @@ impl OxAgWorldGeneratorBuilder {
             score: self.score.unwrap_or(DEFAULT_SCORE),
             with_info: self.with_info.unwrap_or(true),
             maze: self.maze.unwrap_or(false),
+            maze_algorithm: self.maze_algorithm,
+            maze_braid: self.maze_braid,
+            maze_loops: self.maze_loops,
+            maze_goal_percentile: self.maze_goal_percentile,
+            maze_solution: None,
+            maze_difficulty: self.maze_difficulty.clone(),
+            maze_metrics: None,
+            dungeon_options: self.dungeon_options.clone(),
+            dungeon_layout: None,
+            teleport_options: self.teleport_options.clone(),
+            teleport_report: None,
             score_map: self.score_map.clone(),
             map_save: None,
+            placement_report: vec![],
+            spawn_report: None,
+            connectivity_report: None,
         })
     }
 
@@ -154,10 +246,20 @@ impl OxAgWorldGeneratorBuilder {
             seed: None,
             tile_type_options: None,
             content_options: None,
+            region_options: vec![],
+            spawn_strategy: OxAgSpawnStrategy::default(),
+            connectivity_options: None,
             environmental_conditions: None,
             height_multiplier: None,
             score: None,
             maze: None,
+            maze_algorithm: OxAgMazeAlgorithm::default(),
+            maze_braid: 0.0,
+            maze_loops: 0,
+            maze_goal_percentile: 1.0,
+            maze_difficulty: None,
+            dungeon_options: None,
+            teleport_options: None,
             with_info: None,
             score_map: None,
         }
@@ -196,6 +298,145 @@ impl OxAgWorldGeneratorBuilder {
         self
     }
 
+    /// Sets the algorithm used to carve the maze in the [Builder](OxAgWorldGeneratorBuilder),
+    /// it has no effect if the map is not a maze
+    ///
+    /// Returns the [Builder](OxAgWorldGeneratorBuilder)
+    pub fn set_maze_algorithm(mut self, maze_algorithm: OxAgMazeAlgorithm) -> Self {
+        self.maze_algorithm = maze_algorithm;
+        self
+    }
+
+    /// Sets the share of the dead ends of the maze that are opened in the [Builder](OxAgWorldGeneratorBuilder),
+    /// 0 keeps every dead end and 1 removes all of them.
+    /// Opening a dead end adds a loop, so that there are several paths between two tiles.
+    ///
+    /// Returns a [Result] of the [Builder](OxAgWorldGeneratorBuilder) or an [OxAgError] if the factor is not between 0 and 1.
+    pub fn set_maze_braid(mut self, maze_braid: f64) -> Result<Self, OxAgError> {
+        if !(0.0..=1.0).contains(&maze_braid) {
+            return Err(InvalidMazeBraid);
+        }
+        self.maze_braid = maze_braid;
+        Ok(self)
+    }
+
+    /// Sets the number of random walls of the maze that are opened in the [Builder](OxAgWorldGeneratorBuilder),
+    /// each of them adds a loop.
+    ///
+    /// Returns the [Builder](OxAgWorldGeneratorBuilder)
+    pub fn set_maze_loops(mut self, maze_loops: usize) -> Self {
+        self.maze_loops = maze_loops;
+        self
+    }
+
+    /// Sets where the goal of the maze is placed in the [Builder](OxAgWorldGeneratorBuilder),
+    /// as a percentile of the shortest path distances from the spawn:
+    /// 1 is the farthest reachable tile, 0.5 the median one.
+    ///
+    /// Returns a [Result] of the [Builder](OxAgWorldGeneratorBuilder) or an [OxAgError] if the percentile is not between 0 and 1.
+    pub fn set_maze_goal_percentile(
+        mut self,
+        maze_goal_percentile: f64,
+    ) -> Result<Self, OxAgError> {
+        if !(0.0..=1.0).contains(&maze_goal_percentile) {
+            return Err(InvalidMazeGoalPercentile);
+        }
+        self.maze_goal_percentile = maze_goal_percentile;
+        Ok(self)
+    }
+
+    /// Sets the [OxAgMazeDifficulty] of the maze or dungeon of the [Builder](OxAgWorldGeneratorBuilder).
+    ///
+    /// The maze is regenerated with seeds derived from the generator one until its metrics fall in the band,
+    /// if none does after a few attempts the last one is kept: [gen](robotics_lib::world::world_generator::Generator::gen)
+    /// returns it anyway, while [try_generate](crate::world_generator::OxAgWorldGenerator::try_generate)
+    /// fails with [MazeDifficultyNotReached](OxAgError::MazeDifficultyNotReached).
+    ///
+    /// Returns a [Result] of the [Builder](OxAgWorldGeneratorBuilder) or an [OxAgError] if the bands are invalid.
+    ///
+    /// # Example
+    /// ```rust
+    /// use oxagworldgenerator::world_generator::maze_metrics::OxAgMazeDifficulty;
+    /// use oxagworldgenerator::world_generator::world_generator_builder::OxAgWorldGeneratorBuilder;
+    ///
+    /// let generator = OxAgWorldGeneratorBuilder::new()
+    ///     .set_size(31)
+    ///     .set_maze(true)
+    ///     .set_maze_difficulty(OxAgMazeDifficulty::Hard)
+    ///     .unwrap()
+    ///     .build();
+    /// ```
+    pub fn set_maze_difficulty(
+        mut self,
+        maze_difficulty: OxAgMazeDifficulty,
+    ) -> Result<Self, OxAgError> {
+        maze_difficulty.validate()?;
+        self.maze_difficulty = Some(maze_difficulty);
+        Ok(self)
+    }
+
+    /// Sets the [Builder](OxAgWorldGeneratorBuilder) to build a dungeon: rooms connected by corridors,
+    /// surrounded by walls. It can't be combined with [set_maze](OxAgWorldGeneratorBuilder::set_maze).
+    /// This will also perform a check to validate the provided options.
+    ///
+    /// The rooms, doors and chokepoints of the generated dungeon are given by the [OxAgWorldGenerator].
+    ///
+    /// Returns a [Result] of the [Builder](OxAgWorldGeneratorBuilder) or an [OxAgError] if the options are invalid.
+    ///
+    /// # Example
+    /// ```rust
+    /// use oxagworldgenerator::world_generator::dungeon::{OxAgDungeonOptions, OxAgRoomShape, OxAgRoomTheme};
+    /// use oxagworldgenerator::world_generator::world_generator_builder::OxAgWorldGeneratorBuilder;
+    ///
+    /// let generator = OxAgWorldGeneratorBuilder::new()
+    ///     .set_size(64)
+    ///     .set_dungeon(OxAgDungeonOptions {
+    ///         room_shape: OxAgRoomShape::Cave,
+    ///         themes: vec![(OxAgRoomTheme::Treasure, 0.5)],
+    ///         ..Default::default()
+    ///     })
+    ///     .unwrap()
+    ///     .build();
+    /// ```
+    pub fn set_dungeon(mut self, dungeon_options: OxAgDungeonOptions) -> Result<Self, OxAgError> {
+        dungeon_options.validate()?;
+        self.dungeon_options = Some(dungeon_options);
+        Ok(self)
+    }
+
+    /// Sets the teleports of the [Builder](OxAgWorldGeneratorBuilder): pairs of `Teleport` tiles,
+    /// each one in its own sector of the map and with walkable tiles around it, in mazes, dungeons and open worlds.
+    /// This will also perform a check to validate the provided options.
+    ///
+    /// The pairs, the walkable regions they link and how much of the world they make reachable
+    /// are given by the [OxAgWorldGenerator].
+    ///
+    /// Returns a [Result] of the [Builder](OxAgWorldGeneratorBuilder) or an [OxAgError] if the options are invalid.
+    ///
+    /// # Example
+    /// ```rust
+    /// use oxagworldgenerator::world_generator::teleports::OxAgTeleportOptions;
+    /// use oxagworldgenerator::world_generator::world_generator_builder::OxAgWorldGeneratorBuilder;
+    ///
+    /// let generator = OxAgWorldGeneratorBuilder::new()
+    ///     .set_size(128)
+    ///     .set_teleports(OxAgTeleportOptions {
+    ///         pairs: 4,
+    ///         min_distance: 30,
+    ///         ..Default::default()
+    ///     })
+    ///     .unwrap()
+    ///     .build();
+    /// ```
+    pub fn set_teleports(
+        mut self,
+        teleport_options: OxAgTeleportOptions,
+    ) -> Result<Self, OxAgError> {
+        teleport_options.validate()?;
+        self.teleport_options = Some(teleport_options);
+        Ok(self)
+    }
+
     ///  Sets the with_info of the [Builder](OxAgWorldGeneratorBuilder)
     ///
     /// Returns the [Builder](OxAgWorldGeneratorBuilder)
@@ -240,6 +481,7 @@ impl OxAgWorldGeneratorBuilder {
         content_options: Vec<(Content, OxAgContentOptions)>,
     ) -> Result<Self, OxAgError> {
         content_options.validate()?;
+        check_anchor_loops(&content_options, &self.region_options)?;
         self.content_options = Some(content_options);
         Ok(self)
     }
@@ -277,31 +519,127 @@ impl OxAgWorldGeneratorBuilder {
     /// Modifies a single tile content spawn options.
     /// This will also perform a check to validate the provided options.
     ///
-    /// Returns a [Result] of the [Builder](OxAgWorldGeneratorBuilder) or an [OxAgError] if the options are invalid.
+    /// Returns a [Result] of the [Builder](OxAgWorldGeneratorBuilder) or an [OxAgError] if the options are invalid
+    /// or their anchor closes a loop with the other global and region options.
     pub fn alter_content_option(
         mut self,
         content: Content,
         content_option: OxAgContentOptions,
     ) -> Result<Self, OxAgError> {
-        content_option.validate()?;
-        let found = self
-            .content_options
-            .as_mut()
-            .ok_or(ContentOptionsNotSet)?
-            .iter_mut()
-            .any(|(c, opt)| {
-                if *c == content.to_default() {
-                    *opt = content_option;
-                    true
-                } else {
-                    false
-                }
-            });
-        if !found {
-            let mut option = self.content_options.clone().unwrap();
-            option.push((content.to_default(), content_option));
-            self.content_options = Some(option);
+        content_option.validate_for(&content)?;
+        let options = self.content_options.as_mut().ok_or(ContentOptionsNotSet)?;
+        match options.iter_mut().find(|(c, _)| *c == content.to_default()) {
+            Some((_, opt)) => *opt = content_option,
+            None => options.push((content.to_default(), content_option)),
         }
+        check_anchor_loops(options, &self.region_options)?;
+        Ok(self)
+    }
+
+    /// Adds options of a tile content that take the place of the global ones inside a region,
+    /// e.g. a resource-poor area around the spawn.
+    /// Outside of its regions the content keeps following the global options.
+    /// This will also perform a check to validate the provided options.
+    ///
+    /// Returns a [Result] of the [Builder](OxAgWorldGeneratorBuilder) or an [OxAgError] if the options are invalid
+    /// or their anchor closes a loop with the other global and region options.
+    ///
+    /// # Example
+    /// ```rust
+    /// use oxagworldgenerator::world_generator::content_options::OxAgContentOptions;
+    /// use oxagworldgenerator::world_generator::presets::content_presets::OxAgContentPresets;
+    /// use oxagworldgenerator::world_generator::regions::OxAgRegion;
+    /// use oxagworldgenerator::world_generator::world_generator_builder::OxAgWorldGeneratorBuilder;
+    /// use robotics_lib::world::tile::Content;
+    ///
+    /// let generator = OxAgWorldGeneratorBuilder::new()
+    ///     .set_content_options_from_preset(OxAgContentPresets::Default)
+    ///     // No coins within 20 tiles of the spawn
+    ///     .add_region_content_option(
+    ///         OxAgRegion::DistanceFromSpawn(0..=20),
+    ///         Content::Coin(0),
+    ///         OxAgContentOptions::default(),
+    ///     )
+    ///     .unwrap()
+    ///     .build();
+    /// ```
+    pub fn add_region_content_option(
+        mut self,
+        region: OxAgRegion,
+        content: Content,
+        content_option: OxAgContentOptions,
+    ) -> Result<Self, OxAgError> {
+        content_option.validate_for(&content)?;
+        if matches!(&region, OxAgRegion::DistanceFromSpawn(range) if range.is_empty()) {
+            return Err(InvalidRegion);
+        }
+        self.region_options
+            .push((region, content.to_default(), content_option));
+        check_anchor_loops(
+            self.content_options.as_deref().unwrap_or_default(),
+            &self.region_options,
+        )?;
+        Ok(self)
+    }
+
+    /// Sets how the robot spawn is chosen, a random walkable tile by default.
+    ///
+    /// When the strategy can't be followed the closest or best tile found is used,
+    /// see the spawn report of the [OxAgWorldGenerator].
+    ///
+    /// The strategies that depend on the contents, [Resources](OxAgSpawnStrategy::Resources)
+    /// and [AwayFrom](OxAgSpawnStrategy::AwayFrom) with some contents, can move the spawn once
+    /// the contents are placed: [build](OxAgWorldGeneratorBuilder::build) fails if they are
+    /// combined with the connectivity options, the teleports or with regions measured from the spawn.
+    ///
+    /// Returns the [Builder](OxAgWorldGeneratorBuilder)
+    ///
+    /// # Example
+    /// ```rust
+    /// use oxagworldgenerator::world_generator::spawn_strategy::OxAgSpawnStrategy;
+    /// use oxagworldgenerator::world_generator::world_generator_builder::OxAgWorldGeneratorBuilder;
+    /// use robotics_lib::world::tile::{Content, TileType};
+    ///
+    /// let generator = OxAgWorldGeneratorBuilder::new()
+    ///     .set_spawn_strategy(OxAgSpawnStrategy::AwayFrom {
+    ///         tile_types: vec![TileType::Lava, TileType::DeepWater],
+    ///         contents: vec![Content::Fire],
+    ///         min_distance: 5,
+    ///     })
+    ///     .build();
+    /// ```
+    pub fn set_spawn_strategy(mut self, spawn_strategy: OxAgSpawnStrategy) -> Self {
+        self.spawn_strategy = spawn_strategy;
+        self
+    }
+
+    /// Sets the options used to repair the map once the spawn is chosen,
+    /// carving paths until enough of the walkable tiles are reachable from it.
+    /// This will also perform a check to validate the provided options.
+    ///
+    /// Returns a [Result] of the [Builder](OxAgWorldGeneratorBuilder) or an [OxAgError] if the options are invalid.
+    ///
+    /// # Example
+    /// ```rust
+    /// use oxagworldgenerator::world_generator::connectivity::OxAgConnectivityOptions;
+    /// use oxagworldgenerator::world_generator::world_generator_builder::OxAgWorldGeneratorBuilder;
+    /// use robotics_lib::world::tile::TileType;
+    ///
+    /// let generator = OxAgWorldGeneratorBuilder::new()
+    ///     .set_connectivity_options(OxAgConnectivityOptions {
+    ///         min_reachable: 0.95,
+    ///         path_tile_type: TileType::Sand,
+    ///         ..Default::default()
+    ///     })
+    ///     .unwrap()
+    ///     .build();
+    /// ```
+    pub fn set_connectivity_options(
+        mut self,
+        connectivity_options: OxAgConnectivityOptions,
+    ) -> Result<Self, OxAgError> {
+        connectivity_options.validate()?;
+        self.connectivity_options = Some(connectivity_options);
         Ok(self)
     }
 }